@@ -7,38 +7,20 @@
     You need to implement the function `fib(n: i32) -> i32` to return the `n`th Fibonacci number.
     
     Hint: Consider using matrix exponentiation to solve the problem in O(log n) time complexity.
+
+    `fib` panics if `n` is negative, or if n > 46 so that F(n) does not fit in an i32.
 */
 
 use std::fmt::{self, Display, Formatter};
 
-pub fn fib(n: i32) -> i32 {
-    if n == 0 {
-        return 0;
-    }
-    if n == 1 {
-        return 1;
-    }
-    let mut base = [[1, 1], [1, 0]];
-    let mut res = [[1, 0], [0, 1]]; //unit matrix
-    let mut exp = n - 1;
+// 与 normal/solution5 共用的斐波那契模块，这里只用到 checked_nth
+#[allow(dead_code)]
+#[path = "../normal/solution5/src/fibonacci.rs"]
+mod fibonacci;
 
-    while exp > 0 {
-        if exp % 2 == 1 {
-            res = [
-                [res[0][0] * base[0][0] + res[0][1] * base[1][0], res[0][0] * base[0][1] + res[0][1] * base[1][1]],
-                [res[1][0] * base[0][0] + res[1][1] * base[1][0], res[1][0] * base[0][1] + res[1][1] * base[1][1]],
-            ];
-        }
-        base = [
-            [base[0][0] * base[0][0] + base[0][1] * base[1][0], base[0][0] * base[0][1] + base[0][1] * base[1][1]],
-            [base[1][0] * base[0][0] + base[1][1] * base[1][0], base[1][0] * base[0][1] + base[1][1] * base[1][1]],
-        ];
-        exp /= 2;
-    }
-
-    res[0][0]
-    // TODO: Implement the logic to calculate the nth Fibonacci number using matrix exponentiation
-     // Placeholder return value
+pub fn fib(n: i32) -> i32 {
+    assert!(n >= 0, "n must not be negative");
+    fibonacci::checked_nth::<i32>(n as u64).expect("F(n) overflows i32")
 }
 
 #[cfg(test)]
@@ -86,4 +68,18 @@ mod tests {
         println!("Fibonacci of 20: {}", result);
         assert_eq!(result, 6765);
     }
+
+    #[test]
+    fn test_fib_7() {
+        let result = fib(46);
+        println!("Fibonacci of 46: {}", result);
+        assert_eq!(result, 1836311903);
+    }
+
+    #[test]
+    fn test_fib_8() {
+        assert_eq!(fib(46), 1836311903);
+        assert!(std::panic::catch_unwind(|| fib(47)).is_err());
+        assert!(std::panic::catch_unwind(|| fib(-1)).is_err());
+    }
 }
@@ -0,0 +1,13 @@
+//! 困难题测试共用的时间限制
+
+use std::time::Duration;
+
+/// 评测程序会根据机器性能设置 TIME_BUDGET_SCALE，按比例放宽时间限制
+pub fn time_budget(base: Duration) -> Duration {
+    let scale = std::env::var("TIME_BUDGET_SCALE")
+        .ok()
+        .and_then(|s| s.parse::<f64>().ok())
+        .filter(|s| s.is_finite() && *s >= 1.0)
+        .unwrap_or(1.0);
+    base.mul_f64(scale)
+}
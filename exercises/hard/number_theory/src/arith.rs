@@ -0,0 +1,234 @@
+//! Modular arithmetic on u128 without overflow
+
+/// Computes `a * b mod m` without overflowing, for any modulus `m > 0`
+pub fn mul_mod(a: u128, b: u128, m: u128) -> u128 {
+    let (a, b) = (a % m, b % m);
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+
+    // Reduce the 256-bit product one bit at a time
+    let (hi, lo) = mul_wide(a, b);
+    let mut r = hi % m;
+    for bit in (0..128).rev() {
+        let (doubled, overflow) = r.overflowing_add(r);
+        r = if overflow || doubled >= m {
+            doubled.wrapping_sub(m)
+        } else {
+            doubled
+        };
+        if (lo >> bit) & 1 == 1 {
+            r = add_mod(r, 1, m);
+        }
+    }
+    r
+}
+
+/// Computes `base^exp mod m` for any modulus `m > 0`
+pub fn pow_mod(base: u128, mut exp: u128, m: u128) -> u128 {
+    if m == 1 {
+        return 0;
+    }
+    if m % 2 == 1 {
+        let mont = Montgomery::new(m);
+        return mont.decode(mont.pow(mont.encode(base), exp));
+    }
+
+    let mut base = base % m;
+    let mut result = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Greatest common divisor; `gcd(0, 0)` is 0
+pub fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple; `lcm(0, n)` is 0
+///
+/// # Returns
+/// None if the result does not fit in a u128
+pub fn lcm(a: u128, b: u128) -> Option<u128> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Extended Euclidean algorithm
+///
+/// # Returns
+/// `(g, x, y)` with `g = gcd(a, b) >= 0` and `a * x + b * y = g`
+///
+/// # Panics
+/// Panics if `a` or `b` is `i128::MIN`: the gcd can then be 2^127, which an
+/// i128 cannot hold, and the first division step can overflow
+pub fn ext_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    assert!(
+        a != i128::MIN && b != i128::MIN,
+        "ext_gcd does not accept i128::MIN"
+    );
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// Modular inverse of `a` modulo `m`
+///
+/// Works for the whole u128 range by keeping the Bezout coefficient reduced
+/// modulo `m` instead of as a signed number.
+///
+/// # Returns
+/// None if `a` and `m` are not coprime or `m` is 0
+pub fn mod_inverse(a: u128, m: u128) -> Option<u128> {
+    if m == 0 {
+        return None;
+    }
+    let (mut old_r, mut r) = (a % m, m);
+    let (mut old_x, mut x) = (1 % m, 0);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        let qx = mul_mod(q, x, m);
+        (old_x, x) = (x, sub_mod(old_x, qx, m));
+    }
+    (old_r == 1).then_some(old_x)
+}
+
+/// `a + b mod m` for `a, b < m`
+pub(crate) fn add_mod(a: u128, b: u128, m: u128) -> u128 {
+    let (sum, overflow) = a.overflowing_add(b);
+    if overflow || sum >= m {
+        sum.wrapping_sub(m)
+    } else {
+        sum
+    }
+}
+
+/// `a - b mod m` for `a, b < m`
+pub(crate) fn sub_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= b {
+        a - b
+    } else {
+        a.wrapping_sub(b).wrapping_add(m)
+    }
+}
+
+/// The full 256-bit product of two u128 values as (high, low) halves
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+
+    let ll = a_lo * b_lo;
+    let lh = a_lo * b_hi;
+    let hl = a_hi * b_lo;
+    let hh = a_hi * b_hi;
+
+    // At most three 64-bit values, so this cannot overflow
+    let mid = (ll >> 64) + (lh & MASK) + (hl & MASK);
+    let lo = (ll & MASK) | (mid << 64);
+    let hi = hh + (lh >> 64) + (hl >> 64) + (mid >> 64);
+    (hi, lo)
+}
+
+/// Montgomery arithmetic modulo an odd `n`, with R = 2^128
+///
+/// Values are kept as `x * R mod n`, which turns each modular multiplication
+/// into a widening multiply and a reduction without any division.
+pub(crate) struct Montgomery {
+    pub(crate) n: u128,
+    /// -n^-1 mod R
+    n_neg_inv: u128,
+    /// R^2 mod n, used to convert into Montgomery form
+    r2: u128,
+    /// R mod n, the Montgomery form of 1
+    pub(crate) one: u128,
+}
+
+impl Montgomery {
+    pub(crate) fn new(n: u128) -> Self {
+        debug_assert!(n % 2 == 1, "Montgomery form needs an odd modulus");
+
+        // Newton's iteration doubles the number of correct low bits each
+        // step, starting from 3 (n * n = 1 mod 8 for odd n)
+        let mut inv = n;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u128.wrapping_sub(n.wrapping_mul(inv)));
+        }
+
+        let one = (u128::MAX % n + 1) % n;
+        let mut r2 = one;
+        for _ in 0..128 {
+            r2 = add_mod(r2, r2, n);
+        }
+
+        Montgomery {
+            n,
+            n_neg_inv: inv.wrapping_neg(),
+            r2,
+            one,
+        }
+    }
+
+    /// Computes `T / R mod n` for a 256-bit `T = (hi, lo) < n * R`
+    fn reduce(&self, hi: u128, lo: u128) -> u128 {
+        let m = lo.wrapping_mul(self.n_neg_inv);
+        let (mn_hi, mn_lo) = mul_wide(m, self.n);
+        // The low halves sum to a multiple of R; only the carry matters
+        let carry = lo.overflowing_add(mn_lo).1 as u128;
+        let (t, c1) = hi.overflowing_add(mn_hi);
+        let (t, c2) = t.overflowing_add(carry);
+        if c1 || c2 || t >= self.n {
+            t.wrapping_sub(self.n)
+        } else {
+            t
+        }
+    }
+
+    pub(crate) fn mul(&self, a: u128, b: u128) -> u128 {
+        let (hi, lo) = mul_wide(a, b);
+        self.reduce(hi, lo)
+    }
+
+    pub(crate) fn encode(&self, a: u128) -> u128 {
+        self.mul(a % self.n, self.r2)
+    }
+
+    pub(crate) fn decode(&self, a: u128) -> u128 {
+        self.reduce(0, a)
+    }
+
+    pub(crate) fn pow(&self, mut base: u128, mut exp: u128) -> u128 {
+        let mut result = self.one;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = self.mul(result, base);
+            }
+            base = self.mul(base, base);
+            exp >>= 1;
+        }
+        result
+    }
+}
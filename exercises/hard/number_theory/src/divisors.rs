@@ -0,0 +1,66 @@
+//! Multiplicative functions built on the prime factorization
+
+use crate::primes::factorize;
+
+/// Euler's totient: how many of `1..=n` are coprime to `n`
+///
+/// `totient(0)` is 0.
+pub fn totient(n: u128) -> u128 {
+    if n == 0 {
+        return 0;
+    }
+    factorize(n)
+        .into_iter()
+        .fold(n, |phi, (p, _)| phi / p * (p - 1))
+}
+
+/// Returns Euler's totient of every number up to and including `limit`
+///
+/// Uses a sieve, which is much faster than calling [`totient`] in a loop.
+pub fn totients_up_to(limit: usize) -> Vec<u64> {
+    let mut phi: Vec<u64> = (0..=limit as u64).collect();
+    for p in 2..=limit {
+        // phi[p] is still p exactly when no smaller prime divided it
+        if phi[p] == p as u64 {
+            for multiple in (p..=limit).step_by(p) {
+                phi[multiple] = phi[multiple] / p as u64 * (p as u64 - 1);
+            }
+        }
+    }
+    phi
+}
+
+/// Returns every positive divisor of `n` in ascending order
+///
+/// `divisors(0)` is empty.
+pub fn divisors(n: u128) -> Vec<u128> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut result = vec![1];
+    for (p, exponent) in factorize(n) {
+        let current = result.len();
+        let mut power = 1;
+        for _ in 0..exponent {
+            power *= p;
+            for i in 0..current {
+                result.push(result[i] * power);
+            }
+        }
+    }
+    result.sort_unstable();
+    result
+}
+
+/// Counts the positive divisors of `n` without listing them
+///
+/// `divisor_count(0)` is 0.
+pub fn divisor_count(n: u128) -> u64 {
+    if n == 0 {
+        return 0;
+    }
+    factorize(n)
+        .into_iter()
+        .map(|(_, exponent)| u64::from(exponent) + 1)
+        .product()
+}
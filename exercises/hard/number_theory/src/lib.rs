@@ -0,0 +1,18 @@
+//! Number theory routines shared by the hard exercises
+//!
+//! - [`sieve`], [`primes_up_to`], [`primes_between`] and [`primes`]: the
+//!   Sieve of Eratosthenes, segmented for wide ranges and endless iteration
+//! - [`is_prime`] and [`factorize`]: Miller-Rabin, Baillie-PSW and Pollard's rho
+//!   for u128; primality is proven only below about 2^81.5, see [`is_prime`]
+//! - [`totient`], [`divisors`], [`divisor_count`]: functions of the factorization
+//! - [`gcd`], [`lcm`], [`ext_gcd`], [`mod_inverse`], [`mul_mod`], [`pow_mod`]
+
+mod arith;
+mod divisors;
+mod primes;
+mod sieve;
+
+pub use arith::{ext_gcd, gcd, lcm, mod_inverse, mul_mod, pow_mod};
+pub use divisors::{divisor_count, divisors, totient, totients_up_to};
+pub use primes::{factorize, is_prime};
+pub use sieve::{primes, primes_between, primes_up_to, sieve, Primes};
@@ -0,0 +1,350 @@
+//! Primality testing and factorization of u128 values
+
+use crate::arith::{add_mod, gcd, sub_mod, Montgomery};
+
+/// The primes below 72, ruled out by division before any probable-prime test
+const SMALL_PRIMES: [u128; 20] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71,
+];
+
+/// Bases that make Miller-Rabin exact for every n < 2^64 (Jim Sinclair)
+const U64_BASES: [u64; 7] = [2, 325, 9375, 28178, 450775, 9780504, 1795265022];
+
+/// The smallest composite that passes Miller-Rabin for all of the first 13
+/// primes as bases, so those bases are exact below it (Sorenson and Webster)
+const PSI_13: u128 = 3_317_044_064_679_887_385_961_981;
+
+/// Factors below this bound are found by trial division before Pollard's rho
+const TRIAL_LIMIT: u128 = 1_000;
+
+/// Determines whether a number is prime
+///
+/// Accepts any unsigned integer up to `u128`. Small factors are ruled out by
+/// trial division. Values that fit in a u64 then get a Miller-Rabin test over
+/// [`U64_BASES`], and values below [`PSI_13`] (about 2^81.5) one over the
+/// first 13 primes; both are proven exact.
+///
+/// The answer is therefore deterministic only below [`PSI_13`]. No
+/// deterministic Miller-Rabin base set is known for the rest of the u128
+/// range, so larger values get the Baillie-PSW test, a strong base-2 test
+/// followed by a strong Lucas test. No composite is known to pass it, but
+/// that is not proven, and a `true` there means "probably prime".
+pub fn is_prime(n: impl Into<u128>) -> bool {
+    let n = n.into();
+    if n < 2 {
+        return false;
+    }
+    for &p in &SMALL_PRIMES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+    if n < 71 * 71 {
+        return true;
+    }
+    if let Ok(n) = u64::try_from(n) {
+        return is_prime_u64(n);
+    }
+
+    let mont = Montgomery::new(n);
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    if n < PSI_13 {
+        return SMALL_PRIMES[..13]
+            .iter()
+            .all(|&a| is_strong_probable_prime(&mont, a, d, s));
+    }
+    is_strong_probable_prime(&mont, 2, d, s) && is_strong_lucas_probable_prime(&mont)
+}
+
+/// Miller-Rabin for an odd n < 2^64 without small factors, using u128
+/// products directly
+fn is_prime_u64(n: u64) -> bool {
+    let n = u128::from(n);
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+
+    U64_BASES.iter().all(|&a| {
+        let a = u128::from(a) % n;
+        // A base that is a multiple of n says nothing about n
+        if a == 0 {
+            return true;
+        }
+        let mut x = pow_mod_u64(a, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = x * x % n;
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// `base^exp mod m` for `base, m < 2^64`, where products fit in a u128
+fn pow_mod_u64(mut base: u128, mut exp: u128, m: u128) -> u128 {
+    let mut result = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    result
+}
+
+/// Returns the prime factorization of `n` as (prime, exponent) pairs in
+/// ascending order of the primes
+///
+/// 0 and 1 have no prime factors, so both return an empty list.
+pub fn factorize(mut n: u128) -> Vec<(u128, u32)> {
+    let mut primes = Vec::new();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mut p = 2;
+    while p < TRIAL_LIMIT && p * p <= n {
+        while n.is_multiple_of(p) {
+            primes.push(p);
+            n /= p;
+        }
+        p += if p == 2 { 1 } else { 2 };
+    }
+    if n > 1 {
+        split(n, &mut primes);
+    }
+
+    primes.sort_unstable();
+    let mut factors: Vec<(u128, u32)> = Vec::new();
+    for p in primes {
+        match factors.last_mut() {
+            Some((last, count)) if *last == p => *count += 1,
+            _ => factors.push((p, 1)),
+        }
+    }
+    factors
+}
+
+/// Splits an odd `n` without factors below [`TRIAL_LIMIT`] into primes
+fn split(n: u128, primes: &mut Vec<u128>) {
+    if n == 1 {
+        return;
+    }
+    if is_prime(n) {
+        primes.push(n);
+        return;
+    }
+    // Pollard's rho needs about sqrt(p) steps to find a factor p, far too
+    // many when n is a power of one large prime
+    if let Some((root, k)) = perfect_power(n) {
+        let mut root_primes = Vec::new();
+        split(root, &mut root_primes);
+        for _ in 0..k {
+            primes.extend_from_slice(&root_primes);
+        }
+        return;
+    }
+    let d = pollard_brent(n);
+    split(d, primes);
+    split(n / d, primes);
+}
+
+/// Returns `(r, k)` with `r^k = n` and `k > 1` as large as possible, or `None`
+/// if `n` is not a perfect power
+///
+/// Only meant for `n` without factors below [`TRIAL_LIMIT`]: then `r` is at
+/// least that large, which bounds `k`.
+fn perfect_power(n: u128) -> Option<(u128, u32)> {
+    let max_k = n.ilog(TRIAL_LIMIT);
+    (2..=max_k).rev().find_map(|k| {
+        let r = integer_root(n, k);
+        (r.checked_pow(k) == Some(n)).then_some((r, k))
+    })
+}
+
+/// The largest `r` with `r^k <= n`, for `k >= 2`
+fn integer_root(n: u128, k: u32) -> u128 {
+    if k == 2 {
+        return n.isqrt();
+    }
+    // A cube root or higher of a u128 is below 2^43, so the floating-point
+    // estimate is off by at most a few units
+    let fits = |r: u128| r.checked_pow(k).is_some_and(|power| power <= n);
+    let mut r = (n as f64).powf(1.0 / f64::from(k)) as u128;
+    while r > 0 && !fits(r) {
+        r -= 1;
+    }
+    while fits(r + 1) {
+        r += 1;
+    }
+    r
+}
+
+/// Finds a non-trivial factor of an odd composite `n` with Brent's variant of
+/// Pollard's rho
+///
+/// The walk x -> x^2 + c runs in Montgomery form; that only scales the
+/// differences by a unit, so the gcds with `n` are unchanged. Differences are
+/// multiplied together and checked with one gcd per batch; if a batch
+/// overshoots to `n`, the walk is replayed one step at a time. A walk that
+/// only finds `n` itself is retried with the next `c`.
+fn pollard_brent(n: u128) -> u128 {
+    const BATCH: u64 = 128;
+    let mont = Montgomery::new(n);
+
+    for c in 1.. {
+        let c = mont.encode(c);
+        let step = |x: u128| add_mod(mont.mul(x, x), c, n);
+
+        let mut y = mont.encode(2);
+        let mut x = y;
+        let mut ys = y;
+        let mut q = mont.one;
+        let mut g = 1;
+        let mut r: u64 = 1;
+
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = step(y);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y;
+                for _ in 0..BATCH.min(r - k) {
+                    y = step(y);
+                    q = mont.mul(q, x.abs_diff(y));
+                }
+                g = gcd(q, n);
+                k += BATCH;
+            }
+            r *= 2;
+        }
+
+        if g == n {
+            loop {
+                ys = step(ys);
+                g = gcd(x.abs_diff(ys), n);
+                if g > 1 {
+                    break;
+                }
+            }
+        }
+        if g != n {
+            return g;
+        }
+    }
+    unreachable!("some c splits every odd composite")
+}
+
+/// One Miller-Rabin round for base `a`, where `n - 1 = d * 2^s` with `d` odd
+fn is_strong_probable_prime(mont: &Montgomery, a: u128, d: u128, s: u32) -> bool {
+    let minus_one = mont.n - mont.one;
+    let mut x = mont.pow(mont.encode(a), d);
+    if x == mont.one || x == minus_one {
+        return true;
+    }
+    for _ in 1..s {
+        x = mont.mul(x, x);
+        if x == minus_one {
+            return true;
+        }
+        if x == mont.one {
+            return false;
+        }
+    }
+    false
+}
+
+/// The strong Lucas probable-prime test with Selfridge's parameters, for an
+/// odd `n` without small factors
+///
+/// D is the first of 5, -7, 9, -11, ... with Jacobi symbol (D/n) = -1, P = 1
+/// and Q = (1 - D) / 4. With `n + 1 = d * 2^s` and `d` odd, a prime `n` has
+/// U(d) = 0 or V(d * 2^r) = 0 (mod n) for some `r < s`.
+fn is_strong_lucas_probable_prime(mont: &Montgomery) -> bool {
+    let n = mont.n;
+    // No D works for a square, and then the search below would never end
+    if n.isqrt().pow(2) == n {
+        return false;
+    }
+    let mut d: i128 = 5;
+    loop {
+        match jacobi(signed_mod(d, n), n) {
+            -1 => break,
+            0 if d.unsigned_abs() != n => return false,
+            _ => d = if d > 0 { -d - 2 } else { -d + 2 },
+        }
+    }
+    let q = (1 - d) / 4;
+    let d = mont.encode(signed_mod(d, n));
+    let q = mont.encode(signed_mod(q, n));
+
+    // Halving mod n commutes with Montgomery form, since it is linear
+    let half = |x: u128| if x.is_multiple_of(2) { x / 2 } else { x / 2 + n / 2 + 1 };
+    let s = (n + 1).trailing_zeros();
+    let k = (n + 1) >> s;
+
+    // (U(j), V(j), Q^j) for j = the bits of k seen so far, starting at j = 1
+    let (mut u, mut v, mut q_j) = (mont.one, mont.one, q);
+    for bit in (0..k.ilog2()).rev() {
+        // j -> 2j: U(2j) = U(j) V(j), V(2j) = V(j)^2 - 2Q^j
+        u = mont.mul(u, v);
+        v = sub_mod(mont.mul(v, v), add_mod(q_j, q_j, n), n);
+        q_j = mont.mul(q_j, q_j);
+        if (k >> bit) & 1 == 1 {
+            // j -> j + 1 with P = 1: U = (U + V) / 2, V = (D U + V) / 2
+            (u, v) = (half(add_mod(u, v, n)), half(add_mod(mont.mul(d, u), v, n)));
+            q_j = mont.mul(q_j, q);
+        }
+    }
+    if u == 0 || v == 0 {
+        return true;
+    }
+    for _ in 1..s {
+        v = sub_mod(mont.mul(v, v), add_mod(q_j, q_j, n), n);
+        q_j = mont.mul(q_j, q_j);
+        if v == 0 {
+            return true;
+        }
+    }
+    false
+}
+
+/// `a mod n` as a value in `0..n`, for a small signed `a`
+fn signed_mod(a: i128, n: u128) -> u128 {
+    if a >= 0 {
+        a.unsigned_abs() % n
+    } else {
+        (n - a.unsigned_abs() % n) % n
+    }
+}
+
+/// The Jacobi symbol (a/n) for an odd `n`
+fn jacobi(mut a: u128, mut n: u128) -> i32 {
+    a %= n;
+    let mut result = 1;
+    while a != 0 {
+        let twos = a.trailing_zeros();
+        a >>= twos;
+        // (2/n) = -1 exactly when n = 3 or 5 (mod 8)
+        if twos % 2 == 1 && matches!(n % 8, 3 | 5) {
+            result = -result;
+        }
+        // Quadratic reciprocity flips the sign when both are 3 (mod 4)
+        if a % 4 == 3 && n % 4 == 3 {
+            result = -result;
+        }
+        (a, n) = (n % a, a);
+    }
+    if n == 1 {
+        result
+    } else {
+        0
+    }
+}
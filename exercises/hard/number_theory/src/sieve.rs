@@ -0,0 +1,151 @@
+//! Sieve of Eratosthenes, whole and segmented
+
+use crate::primes::is_prime;
+
+/// Numbers sieved per segment; small enough to stay in the L1/L2 cache
+const SEGMENT_LEN: u64 = 1 << 15;
+
+/// Returns a table where `table[n]` tells whether `n` is prime, for `n <= limit`
+///
+/// Use this for repeated lookups below a known bound.
+pub fn sieve(limit: usize) -> Vec<bool> {
+    let mut is_prime = vec![true; limit + 1];
+    is_prime[0] = false;
+    if limit >= 1 {
+        is_prime[1] = false;
+    }
+
+    let mut p = 2;
+    while p * p <= limit {
+        if is_prime[p] {
+            for multiple in (p * p..=limit).step_by(p) {
+                is_prime[multiple] = false;
+            }
+        }
+        p += 1;
+    }
+    is_prime
+}
+
+/// Returns the primes up to and including `limit`
+pub fn primes_up_to(limit: u64) -> Vec<u64> {
+    primes_between(0, limit)
+}
+
+/// Returns the primes in `low..=high`
+///
+/// A range narrower than `sqrt(high)` is checked one number at a time with
+/// [`is_prime`]. Wider ranges use a segmented sieve whose sieving primes are
+/// themselves produced segment by segment, so memory use beyond the result
+/// is `O(sqrt(high) / ln(high) + SEGMENT_LEN)`.
+pub fn primes_between(low: u64, high: u64) -> Vec<u64> {
+    if high < low {
+        return Vec::new();
+    }
+    let root = high.isqrt();
+    // Finding the sieving primes alone would cost more than the range
+    if high - low < root {
+        return (low..=high).filter(|&n| is_prime(n)).collect();
+    }
+    let base: Vec<u64> = primes().take_while(|&p| p <= root).collect();
+    let mut primes = Vec::new();
+    let mut start = low;
+    loop {
+        let end = start.saturating_add(SEGMENT_LEN - 1).min(high);
+        sieve_segment(start, end, &base, &mut primes);
+        if end == high {
+            return primes;
+        }
+        start = end + 1;
+    }
+}
+
+/// Returns an endless iterator over the primes in increasing order
+///
+/// The primes are produced one segment at a time, so there is no upper bound
+/// to choose up front.
+pub fn primes() -> Primes {
+    Primes {
+        next_start: 0,
+        base: Vec::new(),
+        base_limit: 0,
+        buffer: Vec::new(),
+        pos: 0,
+    }
+}
+
+/// Iterator returned by [`primes`]
+#[derive(Debug, Clone)]
+pub struct Primes {
+    next_start: u64,
+    /// Sieving primes up to `base_limit`
+    base: Vec<u64>,
+    base_limit: u64,
+    buffer: Vec<u64>,
+    pos: usize,
+}
+
+impl Iterator for Primes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        while self.pos == self.buffer.len() {
+            let start = self.next_start;
+            let end = start.checked_add(SEGMENT_LEN - 1)?;
+            // Grow the sieving primes geometrically as the segments move up
+            if self.base_limit < end.isqrt() {
+                self.base_limit = end.isqrt().saturating_mul(2);
+                self.base = base_primes(self.base_limit);
+            }
+            self.buffer.clear();
+            self.pos = 0;
+            sieve_segment(start, end, &self.base, &mut self.buffer);
+            self.next_start = end + 1;
+        }
+        self.pos += 1;
+        Some(self.buffer[self.pos - 1])
+    }
+}
+
+/// The primes up to `limit`, used to cross off composites in a segment
+fn base_primes(limit: u64) -> Vec<u64> {
+    sieve(limit as usize)
+        .iter()
+        .enumerate()
+        .filter(|&(_, &prime)| prime)
+        .map(|(n, _)| n as u64)
+        .collect()
+}
+
+/// Appends the primes in `start..=end` to `out`
+///
+/// `base` must contain every prime up to `sqrt(end)`.
+fn sieve_segment(start: u64, end: u64, base: &[u64], out: &mut Vec<u64>) {
+    let mut is_prime = vec![true; (end - start + 1) as usize];
+    for &p in base {
+        let Some(square) = p.checked_mul(p) else {
+            break;
+        };
+        if square > end {
+            break;
+        }
+        // First multiple of p in the segment that is not p itself
+        let first = square.max(start.div_ceil(p) * p);
+        let mut multiple = first;
+        while multiple <= end {
+            is_prime[(multiple - start) as usize] = false;
+            match multiple.checked_add(p) {
+                Some(next) => multiple = next,
+                None => break,
+            }
+        }
+    }
+
+    out.extend(
+        is_prime
+            .iter()
+            .enumerate()
+            .filter(|&(offset, &prime)| prime && start + offset as u64 >= 2)
+            .map(|(offset, _)| start + offset as u64),
+    );
+}
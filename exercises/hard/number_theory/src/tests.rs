@@ -0,0 +1,228 @@
+// src/tests.rs
+
+#[cfg(test)]
+mod tests {
+    use number_theory::*;
+
+    // 梅森素数 2^31-1、2^61-1、2^89-1、2^127-1
+    const M31: u128 = (1 << 31) - 1;
+    const M61: u128 = (1 << 61) - 1;
+    const M89: u128 = (1 << 89) - 1;
+    const M127: u128 = (1 << 127) - 1;
+
+    // 性质测试用的随机数生成器（xorshift64*），固定种子保证可复现
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+            self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+        }
+
+        fn below(&mut self, bound: u64) -> u64 {
+            self.next() % bound
+        }
+    }
+
+    fn brute_is_prime(n: u64) -> bool {
+        n >= 2
+            && (2..n)
+                .take_while(|d| d * d <= n)
+                .all(|d| !n.is_multiple_of(d))
+    }
+
+    fn brute_gcd(a: u64, b: u64) -> u64 {
+        if b == 0 {
+            a
+        } else {
+            brute_gcd(b, a % b)
+        }
+    }
+
+    #[test]
+    fn test_is_prime() {
+        // 与试除法逐一对比
+        for n in 0u64..20_000 {
+            assert_eq!(is_prime(n), brute_is_prime(n), "{n}");
+        }
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..300 {
+            let n = rng.below(1 << 32);
+            assert_eq!(is_prime(n), brute_is_prime(n), "{n}");
+        }
+
+        for p in [M31, M61, M89, M127, 18446744073709551557] {
+            assert!(is_prime(p), "{p}");
+        }
+        assert!(is_prime(7u32));
+
+        // Carmichael 数与强伪素数
+        for n in [561u128, 41041, 2047, 3215031751, 3825123056546413051] {
+            assert!(!is_prime(n), "{n}");
+        }
+        // 对前 13 个素数为底都是强伪素数的最小合数
+        assert!(!is_prime(3317044064679887385961981u128));
+        // 对前 12 个素数为底都是强伪素数的最小合数，以及 2^64 与 ψ13 之间的素数
+        assert!(!is_prime(318665857834031151167461u128));
+        assert!(is_prime(1180591620717411303449u128));
+        assert!(is_prime(3317044064679887385960983u128));
+        assert!(!is_prime(M127 - 2));
+
+        // 2^64 以上使用 Baillie-PSW
+        for p in [
+            18446744073709551629u128,
+            1267650600228229401496703205653,
+            1000000000000000000000000000057,
+            340282366920938463463374607431768211297,
+        ] {
+            assert!(is_prime(p), "{p}");
+        }
+        for n in [
+            18446744073709551557u128 * 18446744073709551557,
+            18446744073709551557 * M61,
+            M89 * 1_000_003,
+            1267650600228229401496703205653 * 3,
+        ] {
+            assert!(!is_prime(n), "{n}");
+        }
+    }
+
+    #[test]
+    fn test_factorize() {
+        assert!(factorize(0).is_empty());
+        assert!(factorize(1).is_empty());
+        assert_eq!(factorize(720_720), [(2, 4), (3, 2), (5, 1), (7, 1), (11, 1), (13, 1)]);
+        assert_eq!(factorize(M61 * M31), [(M31, 1), (M61, 1)]);
+
+        // 大素数的幂不能交给 Pollard rho，否则需要约 sqrt(p) 步
+        let p = 18446744073709551557u128;
+        assert_eq!(factorize(p * p), [(p, 2)]);
+        let p = 4398046511093u128;
+        assert_eq!(factorize(p * p * p), [(p, 3)]);
+        assert_eq!(factorize(p * p * 1073741789), [(1073741789, 1), (p, 2)]);
+        assert_eq!(factorize(M61 * M61 * 16), [(2, 4), (M61, 2)]);
+
+        // 两个 7 位素数之积，以及对前 13 个素数为底都是强伪素数的合数
+        assert_eq!(factorize(1000003 * 9999991), [(1000003, 1), (9999991, 1)]);
+        assert_eq!(factorize((1 << 67) - 1), [(193707721, 1), (761838257287, 1)]);
+        assert_eq!(
+            factorize(3317044064679887385961981),
+            [(1287836182261, 1), (2575672364521, 1)]
+        );
+        assert_eq!(factorize(M127), [(M127, 1)]);
+        assert_eq!(factorize(1 << 100), [(2, 100)]);
+    }
+
+    #[test]
+    fn test_sieve() {
+        let table = sieve(10_000);
+        for (n, &prime) in table.iter().enumerate() {
+            assert_eq!(prime, brute_is_prime(n as u64), "{n}");
+        }
+        assert_eq!(sieve(0), vec![false]);
+        assert_eq!(primes_up_to(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert!(primes_up_to(1).is_empty());
+
+        // 分段筛跨越多个分段时与 Miller-Rabin 一致；区间较窄时逐个判断
+        let mut rng = Rng(42);
+        for i in 0..20 {
+            let low = rng.below(if i % 2 == 0 { 1 << 32 } else { 1 << 40 });
+            let high = low + rng.below(100_000);
+            let expected: Vec<u64> = (low..=high).filter(|&n| is_prime(n)).collect();
+            assert_eq!(primes_between(low, high), expected, "{low}..={high}");
+        }
+        assert_eq!(primes_between(0, 2), vec![2]);
+        assert!(primes_between(10, 9).is_empty());
+        // 不能为 u64 上限附近的短区间筛出 2^32 以内的全部素数
+        assert_eq!(primes_between(u64::MAX - 100, u64::MAX), vec![u64::MAX - 94, u64::MAX - 82, u64::MAX - 58]);
+
+        assert_eq!(primes().take(100_000).last(), Some(1_299_709));
+        assert_eq!(primes().take(10).collect::<Vec<_>>(), primes_up_to(29));
+        assert!(primes().take(5_000).eq(primes_up_to(48_611)));
+    }
+
+    #[test]
+    fn test_divisors_and_totient() {
+        let phi = totients_up_to(2_000);
+        for n in 1u64..=2_000 {
+            let brute_divisors: Vec<u128> =
+                (1..=n).filter(|d| n % d == 0).map(u128::from).collect();
+            assert_eq!(divisors(n.into()), brute_divisors, "{n}");
+            assert_eq!(divisor_count(n.into()), brute_divisors.len() as u64, "{n}");
+
+            let coprime = (1..=n).filter(|&k| brute_gcd(k, n) == 1).count() as u64;
+            assert_eq!(phi[n as usize], coprime, "{n}");
+            assert_eq!(totient(n.into()), u128::from(coprime), "{n}");
+        }
+        assert_eq!(totient(0), 0);
+        assert!(divisors(0).is_empty());
+        assert_eq!(totient(M61), M61 - 1);
+        assert_eq!(divisor_count(1 << 100), 101);
+        assert_eq!(divisor_count(720_720), 240);
+    }
+
+    #[test]
+    fn test_gcd_lcm() {
+        let mut rng = Rng(7);
+        for _ in 0..2_000 {
+            let (a, b) = (rng.below(3_000), rng.below(3_000));
+            let g = brute_gcd(a, b);
+            assert_eq!(gcd(a.into(), b.into()), u128::from(g), "{a} {b}");
+            if let Some(expected) = (a * b).checked_div(g) {
+                assert_eq!(lcm(a.into(), b.into()), Some(u128::from(expected)));
+            }
+
+            let (g, x, y) = ext_gcd(a.into(), b.into());
+            assert_eq!(g, i128::from(brute_gcd(a, b)));
+            assert_eq!(i128::from(a) * x + i128::from(b) * y, g);
+        }
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(0, 5), Some(0));
+        assert_eq!(lcm(u128::MAX, u128::MAX - 1), None);
+        assert_eq!(ext_gcd(-12, 18).0, 6);
+        assert_eq!(ext_gcd(i128::MAX, -1), (1, 0, -1));
+        assert!(std::panic::catch_unwind(|| ext_gcd(i128::MIN, -1)).is_err());
+
+        for m in 1u128..200 {
+            for a in 0..m {
+                let expected = (0..m).find(|&x| a * x % m == 1 % m && gcd(a, m) == 1);
+                assert_eq!(mod_inverse(a, m), expected, "{a} mod {m}");
+            }
+        }
+        let inverse = mod_inverse(3, M127).unwrap();
+        assert_eq!(mul_mod(3, inverse, M127), 1);
+        assert_eq!(mod_inverse(2, u128::MAX), Some(u128::MAX / 2 + 1));
+        assert_eq!(mod_inverse(5, 0), None);
+    }
+
+    #[test]
+    fn test_mul_mod() {
+        let mut rng = Rng(99);
+        for _ in 0..2_000 {
+            let (a, b, m) = (rng.next(), rng.next(), rng.next().max(1));
+            let expected = u128::from(a) * u128::from(b) % u128::from(m);
+            assert_eq!(mul_mod(a.into(), b.into(), m.into()), expected);
+
+            let e = rng.below(1_000);
+            let brute = (0..e).fold(1 % u128::from(m), |acc, _| {
+                acc * u128::from(a) % u128::from(m)
+            });
+            assert_eq!(pow_mod(a.into(), e.into(), m.into()), brute);
+        }
+
+        assert_eq!(mul_mod(u128::MAX, u128::MAX, M127), mul_mod(1, 1, M127));
+        assert_eq!(mul_mod(u128::MAX - 1, 2, u128::MAX), u128::MAX - 2);
+        assert_eq!(
+            mul_mod(1 << 100, 1 << 100, (1 << 127) + 1),
+            pow_mod(2, 200, (1 << 127) + 1)
+        );
+        // 费马小定理
+        assert_eq!(pow_mod(3, M127 - 1, M127), 1);
+        assert_eq!(pow_mod(5, M61 - 1, M61), 1);
+        assert_eq!(pow_mod(2, 10, 1 << 64), 1024);
+        assert_eq!(pow_mod(7, 0, 13), 1);
+        assert_eq!(pow_mod(7, 5, 1), 0);
+    }
+}
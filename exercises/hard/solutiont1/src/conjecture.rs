@@ -1,41 +1,120 @@
-pub fn goldbach_conjecture() -> String {
-    // 保持原有素数判断逻辑不变
-    fn is_prime(n: u64) -> bool {
-        if n <= 1 { return false; }
-        if n <= 3 { return true; }
-        if n % 2 == 0 || n % 3 == 0 { return false; }
-        let (mut i, mut w) = (5, 2);
-        while i * i <= n {
-            if n % i == 0 { return false; }
-            i += w;
-            w = 6 - w;
+//! Goldbach's other conjecture: every odd composite number is the sum of a
+//! prime and twice a square, `n = p + 2k²`
+//!
+//! The conjecture is false; [`counterexamples`] finds the numbers that break it.
+
+use number_theory::{is_prime, sieve};
+
+/// Sieve-backed search over the odd numbers up to a limit
+///
+/// The primality table is built once in [`OtherGoldbach::new`], after which
+/// every lookup is a table access.
+#[derive(Debug, Clone)]
+pub struct OtherGoldbach {
+    is_prime: Vec<bool>,
+}
+
+impl OtherGoldbach {
+    /// Sieves the numbers up to and including `limit`
+    pub fn new(limit: u64) -> Self {
+        OtherGoldbach {
+            is_prime: sieve(limit as usize),
         }
-        true
-    }
-
-    // 核心逻辑优化点：平方数预计算
-    let (mut n, mut results) = (9u64, vec![]);
-    while results.len() < 2 {
-        if !is_prime(n) {
-            let mut found = false;
-            let max_k = (n as f64).sqrt().floor() as u64;
-            
-            // 遍历平方数范围缩小到 sqrt(n/2)
-            for k in 1..=max_k {
-                let square = k * k;
-                if let Some(p) = n.checked_sub(2 * square) {
-                    if p >= 2 && is_prime(p) {
-                        found = true;
-                        break;
-                    }
-                }
-            }
-            
-            if !found { results.push(n); }
+    }
+
+    /// The largest number this search can answer for
+    pub fn limit(&self) -> u64 {
+        self.is_prime.len() as u64 - 1
+    }
+
+    /// Whether `n` is an odd composite, the numbers the conjecture is about
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is above [`limit`](Self::limit).
+    pub fn is_odd_composite(&self, n: u64) -> bool {
+        n % 2 == 1 && n > 1 && !self.is_prime[n as usize]
+    }
+
+    /// Writes an odd composite `n` as `p + 2k²`
+    ///
+    /// # Returns
+    ///
+    /// `(p, k)` with the smallest `k`, or `None` if `n` is not an odd
+    /// composite or is a counterexample.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is above [`limit`](Self::limit).
+    pub fn witness(&self, n: u64) -> Option<(u64, u64)> {
+        if !self.is_odd_composite(n) {
+            return None;
+        }
+        decompose(n, |p| self.is_prime[p as usize])
+    }
+
+    /// The counterexamples up to the limit, in ascending order
+    pub fn counterexamples(&self) -> impl Iterator<Item = u64> + '_ {
+        self.odd_composites()
+            .filter(|&n| decompose(n, |p| self.is_prime[p as usize]).is_none())
+    }
+
+    /// Every odd composite up to the limit that satisfies the conjecture,
+    /// together with its `(p, k)` witness
+    pub fn witnesses(&self) -> impl Iterator<Item = (u64, (u64, u64))> + '_ {
+        self.odd_composites()
+            .filter_map(|n| Some((n, decompose(n, |p| self.is_prime[p as usize])?)))
+    }
+
+    fn odd_composites(&self) -> impl Iterator<Item = u64> + '_ {
+        (9..=self.limit())
+            .step_by(2)
+            .filter(|&n| !self.is_prime[n as usize])
+    }
+}
+
+/// Returns the counterexamples up to and including `limit`
+pub fn counterexamples(limit: u64) -> Vec<u64> {
+    OtherGoldbach::new(limit).counterexamples().collect()
+}
+
+/// Writes an odd composite `n` as `p + 2k²` without a precomputed sieve
+///
+/// Primality is checked with Miller-Rabin, so this works for any `n`; use
+/// [`OtherGoldbach`] to check many numbers.
+///
+/// # Returns
+///
+/// `(p, k)` with the smallest `k`, or `None` if `n` is not an odd composite
+/// or is a counterexample.
+pub fn witness(n: u64) -> Option<(u64, u64)> {
+    if n.is_multiple_of(2) || n < 9 || is_prime(n) {
+        return None;
+    }
+    decompose(n, is_prime)
+}
+
+/// Tries `k = 1, 2, ...` while `2k² < n` until `n - 2k²` is prime
+fn decompose(n: u64, is_prime: impl Fn(u64) -> bool) -> Option<(u64, u64)> {
+    (1..)
+        .map(|k| (k, 2 * k * k))
+        .take_while(|&(_, square)| square < n)
+        .find(|&(_, square)| is_prime(n - square))
+        .map(|(k, square)| (n - square, k))
+}
+
+/// Finds the two smallest counterexamples, formatted as `"a,b"`
+pub fn goldbach_conjecture() -> String {
+    // 上界不够时翻倍重新筛，直到找到两个反例
+    let mut limit = 1 << 10;
+    loop {
+        let found: Vec<u64> = OtherGoldbach::new(limit)
+            .counterexamples()
+            .take(2)
+            .collect();
+        if let [first, second] = found[..] {
+            return format!("{first},{second}");
         }
-        n += 2; // 仅检查奇数
+        limit *= 2;
     }
-    
-    // 关键修改点：添加逗号分隔符
-    format!("{},{}", results[0], results[1])
 }
@@ -0,0 +1,177 @@
+//! The classic Goldbach conjecture: every even number greater than 2 is the
+//! sum of two primes
+//!
+//! Each way of writing `n = p + q` with `p <= q` is a partition; the number of
+//! partitions plotted against `n` is the Goldbach comet.
+
+use number_theory::{is_prime, sieve};
+use std::error::Error;
+use std::fmt;
+
+/// Why a number cannot be asked about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GoldbachError {
+    /// The conjecture only covers even numbers
+    Odd(u64),
+    /// The conjecture starts at 4
+    TooSmall(u64),
+    /// The number is above the sieve's limit
+    AboveLimit { n: u64, limit: u64 },
+}
+
+impl fmt::Display for GoldbachError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoldbachError::Odd(n) => write!(f, "{n} is odd"),
+            GoldbachError::TooSmall(n) => write!(f, "{n} is below 4"),
+            GoldbachError::AboveLimit { n, limit } => {
+                write!(f, "{n} is above the sieve limit {limit}")
+            }
+        }
+    }
+}
+
+impl Error for GoldbachError {}
+
+/// One way of writing an even number as `p + q` with primes `p <= q`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Partition {
+    pub p: u64,
+    pub q: u64,
+}
+
+/// Outcome of checking every even number in `4..=limit`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verification {
+    pub limit: u64,
+    /// How many even numbers were checked
+    pub checked: usize,
+    /// Even numbers without a partition; empty unless the conjecture is false
+    pub counterexamples: Vec<u64>,
+    /// The number whose smallest `p` is the largest seen, with that `p`
+    pub hardest: Option<(u64, u64)>,
+}
+
+/// Sieve shared by every query up to a limit
+#[derive(Debug, Clone)]
+pub struct Goldbach {
+    is_prime: Vec<bool>,
+}
+
+impl Goldbach {
+    /// Sieves the numbers up to and including `limit`
+    pub fn new(limit: u64) -> Self {
+        Goldbach {
+            is_prime: sieve(limit as usize),
+        }
+    }
+
+    /// The largest number this sieve can answer for
+    pub fn limit(&self) -> u64 {
+        self.is_prime.len() as u64 - 1
+    }
+
+    /// Returns every partition of `n`, ordered by ascending `p`
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - An even number from 4 up to [`limit`](Self::limit)
+    pub fn partitions(&self, n: u64) -> Result<Vec<Partition>, GoldbachError> {
+        self.check(n)?;
+        Ok(self.partitions_unchecked(n).collect())
+    }
+
+    /// Counts the partitions of `n` without collecting them
+    pub fn partition_count(&self, n: u64) -> Result<usize, GoldbachError> {
+        self.check(n)?;
+        Ok(self.partitions_unchecked(n).count())
+    }
+
+    /// The Goldbach comet: `(n, partition count)` for every even `n` in
+    /// `4..=limit`
+    pub fn comet(&self) -> impl Iterator<Item = (u64, usize)> + '_ {
+        self.evens()
+            .map(|n| (n, self.partitions_unchecked(n).count()))
+    }
+
+    /// Checks every even number up to the limit, stopping at the first
+    /// partition of each
+    pub fn verify(&self) -> Verification {
+        let mut verification = Verification {
+            limit: self.limit(),
+            checked: 0,
+            counterexamples: Vec::new(),
+            hardest: None,
+        };
+        for n in self.evens() {
+            verification.checked += 1;
+            match self.partitions_unchecked(n).next() {
+                Some(Partition { p, .. }) => {
+                    if verification.hardest.is_none_or(|(_, hardest)| p > hardest) {
+                        verification.hardest = Some((n, p));
+                    }
+                }
+                None => verification.counterexamples.push(n),
+            }
+        }
+        verification
+    }
+
+    fn check(&self, n: u64) -> Result<(), GoldbachError> {
+        check_even(n)?;
+        if n > self.limit() {
+            Err(GoldbachError::AboveLimit {
+                n,
+                limit: self.limit(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn evens(&self) -> impl Iterator<Item = u64> {
+        (4..=self.limit()).step_by(2)
+    }
+
+    /// `n` must be even and within the sieve
+    fn partitions_unchecked(&self, n: u64) -> impl Iterator<Item = Partition> + '_ {
+        partitions_where(n, move |m| self.is_prime[m as usize])
+    }
+}
+
+/// Rejects the numbers the conjecture says nothing about
+fn check_even(n: u64) -> Result<(), GoldbachError> {
+    if n % 2 == 1 {
+        Err(GoldbachError::Odd(n))
+    } else if n < 4 {
+        Err(GoldbachError::TooSmall(n))
+    } else {
+        Ok(())
+    }
+}
+
+fn partitions_where<F: Fn(u64) -> bool>(n: u64, is_prime: F) -> impl Iterator<Item = Partition> {
+    (2..=n / 2)
+        .filter(move |&p| is_prime(p) && is_prime(n - p))
+        .map(move |p| Partition { p, q: n - p })
+}
+
+/// Returns every partition of the even number `n`
+///
+/// A single query tests each candidate with [`is_prime`] instead of sieving up
+/// to `n`; use [`Goldbach`] for many queries.
+pub fn partitions(n: u64) -> Result<Vec<Partition>, GoldbachError> {
+    check_even(n)?;
+    Ok(partitions_where(n, is_prime).collect())
+}
+
+/// Counts the partitions of the even number `n`, like [`partitions`]
+pub fn partition_count(n: u64) -> Result<usize, GoldbachError> {
+    check_even(n)?;
+    Ok(partitions_where(n, is_prime).count())
+}
+
+/// Checks every even number in `4..=limit` with one shared sieve
+pub fn verify(limit: u64) -> Verification {
+    Goldbach::new(limit).verify()
+}
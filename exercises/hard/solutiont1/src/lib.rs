@@ -0,0 +1,4 @@
+//! Goldbach's other conjecture and Goldbach partitions
+
+pub mod conjecture;
+pub mod goldbach;
@@ -1,6 +1,6 @@
 // I AM NOT DONE
 
-mod conjecture;
+use solutiont1::conjecture;
 
 fn main() {
     let values = conjecture::goldbach_conjecture();
@@ -1,10 +1,15 @@
 // src/tests.rs
 mod conjecture;
+mod goldbach;
+#[path = "../../common/time_budget.rs"]
+mod time_budget;
 
 #[cfg(test)]
 mod tests {
-    use super::conjecture::goldbach_conjecture;
+    use super::conjecture::{counterexamples, goldbach_conjecture, witness, OtherGoldbach};
+    use super::goldbach::{self, Goldbach, GoldbachError, Partition};
     use std::time::{Instant, Duration};
+    use super::time_budget::time_budget;
 
     // 定义测试用例和预期结果
     const TEST_CASE: &str = "5777,5993";
@@ -18,11 +23,82 @@ mod tests {
 
         // 时间超0.5s，判定不合格
         let mut total_score = 0.0;
-        if duration <= Duration::from_millis(200) && result == TEST_CASE {
+        if duration <= time_budget(Duration::from_millis(200)) && result == TEST_CASE {
             total_score += 100.0;
         }
 
         println!("Total score: {:.2}", total_score);
         assert_eq!(100.00, total_score);
     }
+
+    #[test]
+    fn test_other_goldbach() {
+        assert_eq!(counterexamples(1_000_000), vec![5777, 5993]);
+        assert!(counterexamples(5_000).is_empty());
+
+        let search = OtherGoldbach::new(10_000);
+        assert_eq!(search.limit(), 10_000);
+        assert_eq!(search.counterexamples().collect::<Vec<_>>(), vec![5777, 5993]);
+        assert_eq!(search.witness(9), Some((7, 1)));
+        assert_eq!(search.witness(33), Some((31, 1)));
+        assert_eq!(search.witness(5777), None);
+        // 素数和偶数不在猜想范围内
+        assert_eq!(search.witness(7), None);
+        assert_eq!(search.witness(10), None);
+
+        let mut checked = 0;
+        for (n, (p, k)) in search.witnesses() {
+            assert!(search.is_odd_composite(n));
+            assert_eq!(p + 2 * k * k, n);
+            assert_eq!(witness(n), Some((p, k)), "{n}");
+            checked += 1;
+        }
+        // 9..=9999 中的奇合数共 3771 个，去掉两个反例
+        assert_eq!(checked, 3771 - 2);
+
+        assert_eq!(witness(5993), None);
+        // 超出筛表范围时用 Miller-Rabin
+        let n = 3 * 1_000_000_007;
+        let (p, k) = witness(n).unwrap();
+        assert_eq!(p + 2 * k * k, n);
+        assert_eq!(witness(1_000_000_007), None);
+    }
+
+    #[test]
+    fn test_goldbach_partitions() {
+        let pair = |p, q| Partition { p, q };
+        assert_eq!(goldbach::partitions(4), Ok(vec![pair(2, 2)]));
+        assert_eq!(goldbach::partitions(10), Ok(vec![pair(3, 7), pair(5, 5)]));
+        assert_eq!(goldbach::partition_count(100), Ok(6));
+        assert_eq!(goldbach::partitions(7), Err(GoldbachError::Odd(7)));
+        assert_eq!(goldbach::partitions(2), Err(GoldbachError::TooSmall(2)));
+        // 先校验再计算，不会为很大的奇数分配筛表
+        assert_eq!(goldbach::partitions(u64::MAX), Err(GoldbachError::Odd(u64::MAX)));
+        assert_eq!(goldbach::partition_count(1_000_000), Ok(5_402));
+
+        let sieve = Goldbach::new(1_000);
+        assert_eq!(
+            sieve.partition_count(1_002),
+            Err(GoldbachError::AboveLimit { n: 1_002, limit: 1_000 })
+        );
+        for n in (4..=1_000).step_by(2) {
+            let partitions = sieve.partitions(n).unwrap();
+            assert_eq!(partitions.len(), sieve.partition_count(n).unwrap());
+            for Partition { p, q } in partitions {
+                assert!(p <= q && p + q == n, "{n}");
+            }
+        }
+
+        // 彗星的前几项：4, 6, 8, 10, 12, 14 的分拆数
+        let comet: Vec<usize> = sieve.comet().take(6).map(|(_, count)| count).collect();
+        assert_eq!(comet, vec![1, 1, 1, 2, 1, 2]);
+        assert_eq!(sieve.comet().count(), 499);
+
+        let verification = goldbach::verify(100_000);
+        assert_eq!(verification.limit, 100_000);
+        assert_eq!(verification.checked, 49_999);
+        assert!(verification.counterexamples.is_empty());
+        // 100000 以内最小素数 p 最大的是 63274 = 293 + 62981
+        assert_eq!(verification.hardest, Some((63_274, 293)));
+    }
 }
@@ -0,0 +1,3 @@
+//! Largest prime factors, built on the shared `number_theory` crate
+
+pub mod prime_factor;
@@ -1,6 +1,6 @@
 // I AM NOT DONE
 
-mod prime_factor;
+use solutiont2::prime_factor;
 
 fn main() {
     let number = 100;
@@ -1,10 +1,13 @@
 // src/tests.rs
 mod prime_factor;
+#[path = "../../common/time_budget.rs"]
+mod time_budget;
 
 #[cfg(test)]
 mod tests {
-    use super::prime_factor::find_max_prime_factor;
+    use super::prime_factor::{factorize, find_max_prime_factor, is_prime};
     use std::time::{Instant, Duration};
+    use super::time_budget::time_budget;
 
     // 定义测试用例和预期结果
     const TEST_CASES: &[(u128, u128)] = &[
@@ -30,11 +33,29 @@ mod tests {
             let duration = start.elapsed();
 
             // 时间超3s，判定不合格
-            if duration <= Duration::new(3, 0) && result == *expected {
+            if duration <= time_budget(Duration::new(3, 0)) && result == *expected {
                 total_score += 10.0;
             }
         }
         println!("Total score: {:.2}", total_score);
         assert_eq!(100.00, total_score);
     }
+
+    #[test]
+    fn test_find_max_prime_factor() {
+        // 两个 7 位素数之积，原实现会把它当成素数
+        assert_eq!(find_max_prime_factor(1000003 * 9999991), 9999991);
+        assert_eq!(find_max_prime_factor(1000003 * 1000003 * 2), 1000003);
+        assert_eq!(find_max_prime_factor((1 << 127) - 1), (1 << 127) - 1);
+        assert_eq!(find_max_prime_factor(1 << 100), 2);
+        assert_eq!(find_max_prime_factor(u128::MAX), 67280421310721);
+        assert_eq!(find_max_prime_factor(1), 1);
+        assert_eq!(find_max_prime_factor(0), 0);
+
+        // 结果是素数，且是分解式中最大的素因子
+        for (input, expected) in TEST_CASES {
+            assert!(is_prime(*expected), "{expected}");
+            assert_eq!(factorize(*input).last().map(|&(p, _)| p), Some(*expected));
+        }
+    }
 }
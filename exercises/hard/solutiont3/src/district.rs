@@ -1,84 +1,332 @@
 use std::{
     collections::{BTreeMap, HashMap},
-    fs::File,
-    io::{BufRead, BufReader},
+    fmt, fs,
+    io::{self, Read},
+    path::Path,
 };
 
+use crate::json::{self, Node, ParseError, Position, Value};
+use crate::union_find::DisjointSet;
+
+/// District data: batch number -> city -> connected cities
+pub type Districts = BTreeMap<String, HashMap<String, Vec<String>>>;
+
+/// The district data shipped with this crate, found regardless of the working directory
+pub const DEFAULT_DATA_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/district.json");
+
+/// Supported input formats for district data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFormat {
+    /// An object of batches, each mapping a city to its connected cities
+    Json,
+    /// One `city_a,city_b,batch` connection per line
+    Csv,
+}
+
+impl DataFormat {
+    /// Picks the format from a file extension, defaulting to JSON
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => DataFormat::Csv,
+            _ => DataFormat::Json,
+        }
+    }
+}
+
+/// Errors that can occur while loading district data
+#[derive(Debug)]
+pub enum DistrictError {
+    /// The file could not be read
+    Io(io::Error),
+    /// The file is not valid JSON
+    Json(ParseError),
+    /// The JSON is valid but does not have the expected shape
+    Schema { position: Position, expected: &'static str },
+    /// A CSV line is not a `city_a,city_b,batch` record
+    Csv { line: usize, expected: &'static str },
+}
+
+impl fmt::Display for DistrictError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DistrictError::Io(e) => write!(f, "failed to read district data: {}", e),
+            DistrictError::Json(e) => write!(f, "invalid district JSON: {}", e),
+            DistrictError::Schema { position, expected } => {
+                write!(f, "expected {} at {}", expected, position)
+            }
+            DistrictError::Csv { line, expected } => {
+                write!(f, "expected {} on CSV line {}", expected, line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DistrictError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DistrictError::Io(e) => Some(e),
+            DistrictError::Json(e) => Some(e),
+            DistrictError::Schema { .. } | DistrictError::Csv { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for DistrictError {
+    fn from(e: io::Error) -> Self {
+        DistrictError::Io(e)
+    }
+}
+
+impl From<ParseError> for DistrictError {
+    fn from(e: ParseError) -> Self {
+        DistrictError::Json(e)
+    }
+}
+
 /// Parses a JSON file containing district information into a structured format
 /// 
-/// The function reads a JSON file line by line and organizes it into a nested structure:
+/// The file is organized into a nested structure:
 /// - The outer BTreeMap uses batch numbers as keys
 /// - Each batch contains a HashMap of cities and their connections
 ///
 /// @param filename: Path to the JSON file to parse
 /// @return A structured representation of the district data
-pub fn parse_file(filename: &str) -> BTreeMap<String, HashMap<String, Vec<String>>> {
-    let file = File::open(filename).unwrap();
-    let reader = BufReader::new(file);
-    let mut data = BTreeMap::new(); // Main data structure to store all batches
-    let mut main_key = String::new(); // Current batch key being processed
-
-    // Process the file line by line
-    for line in reader.lines() {
-        let line = line.unwrap().trim().to_string();
-        // Skip empty lines and opening braces
-        if line.is_empty() || line.starts_with('{') {
-            continue;
+pub fn parse_file(filename: &str) -> Result<Districts, DistrictError> {
+    let content = fs::read_to_string(filename)?;
+    parse_str(&content)
+}
+
+/// Reads district data in the given format from any reader
+///
+/// @param reader: The source of the data
+/// @param format: How the data is encoded
+/// @return A structured representation of the district data
+pub fn parse_reader(mut reader: impl Read, format: DataFormat) -> Result<Districts, DistrictError> {
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    match format {
+        DataFormat::Json => parse_str(&content),
+        DataFormat::Csv => parse_csv_str(&content),
+    }
+}
+
+/// Parses district information from a JSON string
+///
+/// The document must be an object of batches, each batch an object mapping a
+/// city to an array of connected cities. A city that appears more than once in
+/// the same batch has its connection lists concatenated.
+///
+/// @param content: The JSON text
+/// @return A structured representation of the district data
+pub fn parse_str(content: &str) -> Result<Districts, DistrictError> {
+    let root = json::parse(content)?;
+    let mut data = BTreeMap::new();
+
+    for (batch_key, batch) in expect_object(root, "an object of batches")? {
+        let cities: &mut HashMap<String, Vec<String>> = data.entry(batch_key).or_default();
+
+        for (city, connections) in expect_object(batch, "an object of cities")? {
+            let mut values = Vec::new();
+            for connection in expect_array(connections, "an array of city names")? {
+                match connection.value {
+                    Value::String(name) => values.push(name),
+                    _ => {
+                        return Err(DistrictError::Schema {
+                            position: connection.position,
+                            expected: "a city name string",
+                        })
+                    }
+                }
+            }
+            cities.entry(city).or_default().extend(values);
         }
+    }
+    Ok(data)
+}
 
-        // Check if this line defines a new batch (e.g. "1": {)
-        if line.contains(": {") {
-            let (key, _) = line.split_once(':').unwrap();
-            main_key = key.trim().trim_matches('"').to_string(); // Extract the batch number
-            data.insert(main_key.clone(), HashMap::new()); // Initialize an empty HashMap for this batch
+/// Parses district information from a CSV edge list
+///
+/// Each line holds one connection as `city_a,city_b,batch`. Blank lines, lines
+/// starting with `#` and a `city_a,city_b,batch` header are skipped, and fields
+/// are trimmed.
+///
+/// @param content: The CSV text
+/// @return A structured representation of the district data
+pub fn parse_csv_str(content: &str) -> Result<Districts, DistrictError> {
+    let mut builder = DistrictsBuilder::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
             continue;
         }
-        
-        // Skip closing braces
-        if line.starts_with('}') {
+
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if fields == ["city_a", "city_b", "batch"] {
             continue;
         }
-        
-        // Parse city and its connections (e.g. "city1": ["city2", "city3"])
-        let parts: Vec<&str> = line.split(':').collect();
-        if parts.len() != 2 {
-            continue; // Skip malformed lines
-        }
 
-        // Extract the city name
-        let key = parts[0].trim().trim_matches('"');
-        
-        // Extract the connected cities
-        let values_str = parts[1]
-            .trim()
-            .trim_matches(',')
-            .trim_matches('[')
-            .trim_matches(']')
-            .trim_matches('"');
-            
-        // Parse the list of connected cities
-        let values: Vec<String> = if values_str.is_empty() {
-            Vec::new() // Empty list if no connections
-        } else {
-            values_str
-                .split(',')
-                .map(|v| v.trim().trim_matches('"').to_string())
-                .collect()
+        let error = |expected| DistrictError::Csv {
+            line: index + 1,
+            expected,
         };
+        match fields[..] {
+            [a, b, batch] if !a.is_empty() && !b.is_empty() && !batch.is_empty() => {
+                builder = builder.connect(batch, a, &[b]);
+            }
+            [_, _, _] => return Err(error("non-empty fields")),
+            _ => return Err(error("three comma-separated fields")),
+        }
+    }
+    Ok(builder.build())
+}
+
+/// Builds district data in memory
+///
+/// ```ignore
+/// let data = DistrictsBuilder::new()
+///     .connect("1", "成都", &["宜宾", "自贡"])
+///     .city("2", "拉萨")
+///     .build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DistrictsBuilder {
+    data: Districts,
+}
+
+impl DistrictsBuilder {
+    pub fn new() -> Self {
+        DistrictsBuilder::default()
+    }
+
+    /// Adds a city with no connections to a batch
+    pub fn city(mut self, batch: &str, city: &str) -> Self {
+        self.cities(batch).entry(city.to_string()).or_default();
+        self
+    }
+
+    /// Connects a city to other cities within a batch
+    pub fn connect(mut self, batch: &str, city: &str, connections: &[&str]) -> Self {
+        self.cities(batch)
+            .entry(city.to_string())
+            .or_default()
+            .extend(connections.iter().map(|c| c.to_string()));
+        self
+    }
+
+    pub fn build(self) -> Districts {
+        self.data
+    }
+
+    fn cities(&mut self, batch: &str) -> &mut HashMap<String, Vec<String>> {
+        self.data.entry(batch.to_string()).or_default()
+    }
+}
+
+fn expect_object(node: Node, expected: &'static str) -> Result<Vec<(String, Node)>, DistrictError> {
+    match node.value {
+        Value::Object(members) => Ok(members),
+        _ => Err(DistrictError::Schema {
+            position: node.position,
+            expected,
+        }),
+    }
+}
 
-        // Add the city and its connections to the current batch
-        let mm: &mut HashMap<String, Vec<String>> = data.get_mut(&main_key).unwrap();
-        if mm.contains_key(key) {
-            // If the city already exists, extend its connections
-            if let Some(map) = mm.get_mut(key) {
-                map.extend(values);
+fn expect_array(node: Node, expected: &'static str) -> Result<Vec<Node>, DistrictError> {
+    match node.value {
+        Value::Array(items) => Ok(items),
+        _ => Err(DistrictError::Schema {
+            position: node.position,
+            expected,
+        }),
+    }
+}
+
+/// Cities grouped into provinces by their connections
+///
+/// Two cities are in the same province if they are directly connected or
+/// connected through other cities. Connections can be added at any time and
+/// queries always reflect everything added so far.
+#[derive(Debug, Clone, Default)]
+pub struct Provinces {
+    ids: HashMap<String, usize>,
+    names: Vec<String>,
+    sets: DisjointSet,
+}
+
+impl Provinces {
+    pub fn new() -> Self {
+        Provinces::default()
+    }
+
+    /// Builds the provinces of one batch of district data
+    ///
+    /// @param batch: Each city mapped to the cities it is connected to
+    pub fn from_batch(batch: &HashMap<String, Vec<String>>) -> Self {
+        let mut provinces = Provinces::new();
+        for (city, connections) in batch {
+            provinces.add_city(city);
+            for other in connections {
+                provinces.connect(city, other);
             }
-        } else {
-            // Otherwise, add a new city with its connections
-            mm.insert(key.to_string(), values);
         }
+        provinces
+    }
+
+    /// Adds a city as its own province if it is not known yet
+    pub fn add_city(&mut self, city: &str) {
+        self.id(city);
+    }
+
+    /// Connects two cities, merging their provinces
+    pub fn connect(&mut self, a: &str, b: &str) {
+        let (a, b) = (self.id(a), self.id(b));
+        self.sets.union(a, b);
+    }
+
+    /// Checks whether two cities belong to the same province
+    ///
+    /// @return false if either city is unknown
+    pub fn same_province(&mut self, a: &str, b: &str) -> bool {
+        match (self.ids.get(a), self.ids.get(b)) {
+            (Some(&a), Some(&b)) => self.sets.connected(a, b),
+            _ => false,
+        }
+    }
+
+    /// The number of provinces
+    pub fn count(&self) -> usize {
+        self.sets.set_count()
+    }
+
+    /// Lists every province as a sorted list of its cities
+    ///
+    /// @return Provinces ordered by their first city
+    pub fn provinces(&mut self) -> Vec<Vec<String>> {
+        let mut groups: HashMap<usize, Vec<String>> = HashMap::new();
+        for id in 0..self.names.len() {
+            let root = self.sets.find(id);
+            groups.entry(root).or_default().push(self.names[id].clone());
+        }
+
+        let mut provinces: Vec<Vec<String>> = groups.into_values().collect();
+        for province in provinces.iter_mut() {
+            province.sort();
+        }
+        provinces.sort();
+        provinces
+    }
+
+    fn id(&mut self, city: &str) -> usize {
+        if let Some(&id) = self.ids.get(city) {
+            return id;
+        }
+        let id = self.sets.make_set();
+        self.ids.insert(city.to_string(), id);
+        self.names.push(city.to_string());
+        id
     }
-    data
 }
 
 /// Counts the number of provinces in each batch of district data
@@ -88,71 +336,35 @@ pub fn parse_file(filename: &str) -> BTreeMap<String, HashMap<String, Vec<String
 /// 
 /// @return A comma-separated string of province counts for each batch
 pub fn count_provinces() -> String {
-    let mut result = Vec::new(); // Store the count of provinces for each batch
-    let data = parse_file("district.json"); // Parse the district data
-    
-    // Process each batch in the data
-    for item in data {
-        // HashMap to store connected components (provinces)
-        let mut list: HashMap<String, Vec<String>> = HashMap::new();
-        
-        // Process each city and its connections in the current batch
-        for (sub_item_key, mut sub_item_values) in item.1 {
-            // Try to get the existing vector for this city
-            let tmp_vec = match list.get_mut(&sub_item_key) {
-                Some(v) => v,
-                None => &mut Vec::new(),
-            };
-            
-            // If the city already exists in a province, extend its connections
-            if tmp_vec.contains(&sub_item_key) {
-                tmp_vec.extend(sub_item_values);
-            } else {
-                // Check if this city or any of its connections are already in existing provinces
-                let mut found_on_keys = Vec::new();
-                for (list_key, list_values) in list.iter_mut() {
-                    // Check if the current city is in any existing province
-                    if list_values.contains(&sub_item_key) {
-                        found_on_keys.push(list_key.clone());
-                    } else {
-                        // Check if any of the city's connections are in an existing province
-                        for kk in sub_item_values.iter() {
-                            if list_values.contains(kk) {
-                                found_on_keys.push(list_key.clone());
-                                break;
-                            }
-                        }
-                    }
-                }
+    count_provinces_from(DEFAULT_DATA_FILE).unwrap_or_else(|e| panic!("{}", e))
+}
 
-                // If no existing provinces contain this city or its connections,
-                // create a new province
-                if list.is_empty() || found_on_keys.is_empty() {
-                    sub_item_values.push(sub_item_key.to_string());
-                    list.insert(sub_item_key, sub_item_values);
-                    continue;
-                }
+/// Counts the provinces of a data file, choosing JSON or CSV by its extension
+///
+/// @param path: Path to a `.json` or `.csv` file
+/// @return A comma-separated string of province counts for each batch
+pub fn count_provinces_from(path: impl AsRef<Path>) -> Result<String, DistrictError> {
+    let path = path.as_ref();
+    let file = fs::File::open(path)?;
+    count_provinces_from_reader(file, DataFormat::from_path(path))
+}
 
-                // Merge this city with all provinces it connects to
-                let mut tmp_v = vec![];
-                tmp_v.push(sub_item_key.to_string());
-                tmp_v.extend(sub_item_values);
-                
-                // Merge all connected provinces
-                for key in found_on_keys {
-                    let t = list.remove(&key).unwrap();
-                    tmp_v.extend(t);
-                }
-                
-                // Create a new merged province
-                list.insert(tmp_v[0].clone(), tmp_v);
-            }
-        }
-        
-        // The number of provinces is the number of entries in the list HashMap
-        result.push(format!("{}", list.len()));
-    }
-    
-    // Join all province counts with commas
-    result.join(",")
+/// Counts the provinces of data read from any reader
+///
+/// @param reader: The source of the data
+/// @param format: How the data is encoded
+/// @return A comma-separated string of province counts for each batch
+pub fn count_provinces_from_reader(reader: impl Read, format: DataFormat) -> Result<String, DistrictError> {
+    Ok(count_provinces_in(&parse_reader(reader, format)?))
+}
+
+/// Counts the provinces of district data already in memory
+///
+/// @param data: District data, e.g. from [`DistrictsBuilder`]
+/// @return A comma-separated string of province counts for each batch
+pub fn count_provinces_in(data: &Districts) -> String {
+    data.values()
+        .map(|batch| Provinces::from_batch(batch).count().to_string())
+        .collect::<Vec<_>>()
+        .join(",")
 }
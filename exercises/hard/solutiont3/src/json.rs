@@ -0,0 +1,399 @@
+use std::{fmt, iter::Peekable, str::Chars};
+
+/// How deeply arrays and objects may nest before the document is rejected
+///
+/// The parser is recursive, so this keeps a hostile document from
+/// overflowing the stack.
+pub const MAX_DEPTH: usize = 128;
+
+/// A 1-based line/column location in the JSON source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// A parsed JSON value
+///
+/// Objects keep their members in source order and keep duplicate keys,
+/// so callers can decide how repeated keys are merged.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Node>),
+    Object(Vec<(String, Node)>),
+}
+
+/// A JSON value together with the position where it starts
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub position: Position,
+    pub value: Value,
+}
+
+/// The reason a JSON document was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    UnexpectedEof,
+    UnexpectedChar(char),
+    InvalidEscape(char),
+    InvalidUnicodeEscape,
+    InvalidNumber,
+    ControlCharacterInString,
+    TrailingCharacters,
+    /// Arrays and objects nest deeper than [`MAX_DEPTH`]
+    TooDeep,
+}
+
+/// A syntax error with the position of the offending character
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub position: Position,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ParseErrorKind::UnexpectedEof => write!(f, "unexpected end of input")?,
+            ParseErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?}", c)?,
+            ParseErrorKind::InvalidEscape(c) => write!(f, "invalid escape sequence \\{}", c)?,
+            ParseErrorKind::InvalidUnicodeEscape => write!(f, "invalid \\u escape sequence")?,
+            ParseErrorKind::InvalidNumber => write!(f, "invalid number")?,
+            ParseErrorKind::ControlCharacterInString => {
+                write!(f, "control character in string")?
+            }
+            ParseErrorKind::TrailingCharacters => write!(f, "trailing characters after value")?,
+            ParseErrorKind::TooDeep => write!(f, "nesting deeper than {} levels", MAX_DEPTH)?,
+        }
+        write!(f, " at {}", self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a complete JSON document
+///
+/// Any valid layout is accepted (minified, pretty-printed, arrays split over
+/// several lines); a leading byte order mark is ignored.
+///
+/// @param input: The JSON text
+/// @return The root value, or the first syntax error with its line and column
+pub fn parse(input: &str) -> Result<Node, ParseError> {
+    let mut parser = Parser {
+        chars: input.strip_prefix('\u{feff}').unwrap_or(input).chars().peekable(),
+        line: 1,
+        column: 1,
+        depth: 0,
+    };
+
+    parser.skip_whitespace();
+    let root = parser.parse_value()?;
+    parser.skip_whitespace();
+    if parser.chars.peek().is_some() {
+        return Err(parser.error(ParseErrorKind::TrailingCharacters));
+    }
+    Ok(root)
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+    /// How many arrays and objects enclose the current value
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn position(&self) -> Position {
+        Position {
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            kind,
+            position: self.position(),
+        }
+    }
+
+    /// Consumes one character, keeping the line/column counters in sync
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn peek_or_eof(&mut self) -> Result<char, ParseError> {
+        match self.chars.peek() {
+            Some(&c) => Ok(c),
+            None => Err(self.error(ParseErrorKind::UnexpectedEof)),
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        let c = self.peek_or_eof()?;
+        if c != expected {
+            return Err(self.error(ParseErrorKind::UnexpectedChar(c)));
+        }
+        self.bump();
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.chars.peek() {
+            self.bump();
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Node, ParseError> {
+        let position = self.position();
+        let value = match self.peek_or_eof()? {
+            '{' => self.nested(Self::parse_object)?,
+            '[' => self.nested(Self::parse_array)?,
+            '"' => Value::String(self.parse_string()?),
+            't' => self.parse_literal("true", Value::Bool(true))?,
+            'f' => self.parse_literal("false", Value::Bool(false))?,
+            'n' => self.parse_literal("null", Value::Null)?,
+            '-' | '0'..='9' => self.parse_number()?,
+            c => return Err(self.error(ParseErrorKind::UnexpectedChar(c))),
+        };
+        Ok(Node { position, value })
+    }
+
+    /// Parses an array or object one level deeper, failing past [`MAX_DEPTH`]
+    fn nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<Value, ParseError>,
+    ) -> Result<Value, ParseError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(ParseErrorKind::TooDeep));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: Value) -> Result<Value, ParseError> {
+        for expected in literal.chars() {
+            self.expect(expected)?;
+        }
+        Ok(value)
+    }
+
+    fn parse_object(&mut self) -> Result<Value, ParseError> {
+        self.expect('{')?;
+        let mut members = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek_or_eof()? == '}' {
+            self.bump();
+            return Ok(Value::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            self.skip_whitespace();
+            members.push((key, self.parse_value()?));
+            self.skip_whitespace();
+
+            match self.peek_or_eof()? {
+                ',' => {
+                    self.bump();
+                }
+                '}' => {
+                    self.bump();
+                    return Ok(Value::Object(members));
+                }
+                c => return Err(self.error(ParseErrorKind::UnexpectedChar(c))),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Value, ParseError> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek_or_eof()? == ']' {
+            self.bump();
+            return Ok(Value::Array(items));
+        }
+
+        loop {
+            self.skip_whitespace();
+            items.push(self.parse_value()?);
+            self.skip_whitespace();
+
+            match self.peek_or_eof()? {
+                ',' => {
+                    self.bump();
+                }
+                ']' => {
+                    self.bump();
+                    return Ok(Value::Array(items));
+                }
+                c => return Err(self.error(ParseErrorKind::UnexpectedChar(c))),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, ParseError> {
+        self.expect('"')?;
+        let mut s = String::new();
+
+        loop {
+            let c = self.peek_or_eof()?;
+            match c {
+                '"' => {
+                    self.bump();
+                    return Ok(s);
+                }
+                '\\' => {
+                    self.bump();
+                    s.push(self.parse_escape()?);
+                }
+                c if (c as u32) < 0x20 => {
+                    return Err(self.error(ParseErrorKind::ControlCharacterInString));
+                }
+                c => {
+                    self.bump();
+                    s.push(c);
+                }
+            }
+        }
+    }
+
+    /// Parses the part of an escape sequence after the backslash
+    fn parse_escape(&mut self) -> Result<char, ParseError> {
+        let c = self.peek_or_eof()?;
+        let escaped = match c {
+            '"' => '"',
+            '\\' => '\\',
+            '/' => '/',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                let position = self.position();
+                self.bump();
+                return self.parse_unicode_escape(position);
+            }
+            c => return Err(self.error(ParseErrorKind::InvalidEscape(c))),
+        };
+        self.bump();
+        Ok(escaped)
+    }
+
+    /// Parses `XXXX` after `\u`, combining UTF-16 surrogate pairs
+    fn parse_unicode_escape(&mut self, position: Position) -> Result<char, ParseError> {
+        let invalid = ParseError {
+            kind: ParseErrorKind::InvalidUnicodeEscape,
+            position,
+        };
+
+        let high = self.parse_hex4()?;
+        let code = match high {
+            0xD800..=0xDBFF => {
+                if self.bump() != Some('\\') || self.bump() != Some('u') {
+                    return Err(invalid);
+                }
+                let low = self.parse_hex4()?;
+                if !(0xDC00..=0xDFFF).contains(&low) {
+                    return Err(invalid);
+                }
+                0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+            }
+            0xDC00..=0xDFFF => return Err(invalid),
+            code => code,
+        };
+        char::from_u32(code).ok_or(invalid)
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, ParseError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let c = self.peek_or_eof()?;
+            let digit = c
+                .to_digit(16)
+                .ok_or_else(|| self.error(ParseErrorKind::InvalidUnicodeEscape))?;
+            self.bump();
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn parse_number(&mut self) -> Result<Value, ParseError> {
+        let mut text = String::new();
+
+        if self.chars.peek() == Some(&'-') {
+            text.push('-');
+            self.bump();
+        }
+
+        // Integer part: a single zero or a non-zero digit followed by more digits
+        match self.peek_or_eof()? {
+            '0' => {
+                text.push('0');
+                self.bump();
+            }
+            '1'..='9' => self.take_digits(&mut text),
+            _ => return Err(self.error(ParseErrorKind::InvalidNumber)),
+        }
+
+        if self.chars.peek() == Some(&'.') {
+            text.push('.');
+            self.bump();
+            if !matches!(self.chars.peek(), Some('0'..='9')) {
+                return Err(self.error(ParseErrorKind::InvalidNumber));
+            }
+            self.take_digits(&mut text);
+        }
+
+        if let Some(&e @ ('e' | 'E')) = self.chars.peek() {
+            text.push(e);
+            self.bump();
+            if let Some(&sign @ ('+' | '-')) = self.chars.peek() {
+                text.push(sign);
+                self.bump();
+            }
+            if !matches!(self.chars.peek(), Some('0'..='9')) {
+                return Err(self.error(ParseErrorKind::InvalidNumber));
+            }
+            self.take_digits(&mut text);
+        }
+
+        text.parse()
+            .map(Value::Number)
+            .map_err(|_| self.error(ParseErrorKind::InvalidNumber))
+    }
+
+    fn take_digits(&mut self, text: &mut String) {
+        while let Some(&c @ '0'..='9') = self.chars.peek() {
+            text.push(c);
+            self.bump();
+        }
+    }
+}
@@ -0,0 +1,5 @@
+//! Provinces from district data: a JSON parser, district loading and union-find
+
+pub mod district;
+pub mod json;
+pub mod union_find;
@@ -1,6 +1,6 @@
 // I AM NOT DONE
 
-mod district;
+use solutiont3::district;
 
 fn main() {
     let provinces = district::count_provinces();
@@ -1,10 +1,21 @@
 // src/tests.rs
 mod district;
+mod json;
+mod union_find;
+#[path = "../../common/time_budget.rs"]
+mod time_budget;
 
 #[cfg(test)]
 mod tests {
-    use super::district::count_provinces;
+    use super::district::{
+        count_provinces, count_provinces_from, count_provinces_from_reader, count_provinces_in,
+        parse_csv_str, parse_file, parse_str, DataFormat, DistrictError, DistrictsBuilder,
+        Provinces, DEFAULT_DATA_FILE,
+    };
+    use super::json::{self, ParseErrorKind, MAX_DEPTH};
+    use super::union_find::DisjointSet;
     use std::time::{Instant, Duration};
+    use super::time_budget::time_budget;
 
     // 定义测试用例和预期结果
     const TEST_CASE: &str = "3,3,2,2,1";
@@ -19,11 +30,144 @@ mod tests {
         // 时间超1s，判定不合格
         let mut total_score = 0.0;
 
-        if duration <= Duration::from_millis(500) && result == TEST_CASE {
+        if duration <= time_budget(Duration::from_millis(500)) && result == TEST_CASE {
             total_score += 100.0;
         }
 
         println!("Total score: {:.2}", total_score);
         assert_eq!(100.00, total_score);
     }
+
+    // 紧凑格式、跨行数组与转义字符都应与格式化后的文件解析结果一致
+    #[test]
+    fn test_parse_any_layout() {
+        let minified = r#"{"1":{"A":["B","C"],"B":[],"A":["D"]},"2":{"\u6210\u90fd":["x\"y"]}}"#;
+        let spread = "{\n  \"1\": {\n    \"A\": [\n      \"B\",\n      \"C\"\n    ],\n    \"B\": [],\n    \"A\": [\"D\"]\n  },\n  \"2\": { \"成都\": [\"x\\\"y\"] }\n}";
+
+        let data = parse_str(minified).unwrap();
+        assert_eq!(data, parse_str(spread).unwrap());
+        assert_eq!(data["1"]["A"], ["B", "C", "D"]);
+        assert!(data["1"]["B"].is_empty());
+        assert_eq!(data["2"]["成都"], ["x\"y"]);
+        assert_eq!(parse_file(DEFAULT_DATA_FILE).unwrap().len(), 5);
+    }
+
+    // 语法错误需给出行列位置，结构不符与读取失败分别报告
+    #[test]
+    fn test_parse_errors() {
+        match parse_str("{\n  \"1\": {\n    \"A\": [\"B\" \"C\"]\n  }\n}") {
+            Err(DistrictError::Json(e)) => {
+                assert_eq!(e.kind, ParseErrorKind::UnexpectedChar('"'));
+                assert_eq!((e.position.line, e.position.column), (3, 15));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        match parse_str(r#"{"1": {"A": ["B", 2]}}"#) {
+            Err(DistrictError::Schema { position, .. }) => {
+                assert_eq!((position.line, position.column), (1, 19));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        assert!(matches!(parse_str(r#"{"1": {}"#), Err(DistrictError::Json(_))));
+
+        // 嵌套层数超过上限时报错，而不是耗尽栈空间
+        let deep = format!("{}{}", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH));
+        assert!(json::parse(&deep).is_ok());
+        let too_deep = "[".repeat(100_000);
+        let e = json::parse(&too_deep).unwrap_err();
+        assert_eq!(e.kind, ParseErrorKind::TooDeep);
+        assert_eq!((e.position.line, e.position.column), (1, MAX_DEPTH + 1));
+        assert!(matches!(parse_file("missing.json"), Err(DistrictError::Io(_))));
+    }
+
+    #[test]
+    fn test_disjoint_set() {
+        let mut sets = DisjointSet::new(6);
+        assert_eq!((sets.len(), sets.set_count()), (6, 6));
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.connected(0, 3));
+        assert!(!sets.connected(0, 4));
+        assert_eq!(sets.set_count(), 3);
+
+        let id = sets.make_set();
+        assert_eq!((id, sets.set_count()), (6, 4));
+        assert!(DisjointSet::default().is_empty());
+    }
+
+    // 逐步添加连接，查询结果应随之更新
+    #[test]
+    fn test_provinces_queries() {
+        let mut provinces = Provinces::new();
+        provinces.connect("成都", "宜宾");
+        provinces.connect("深圳", "广州");
+        provinces.add_city("拉萨");
+        assert_eq!(provinces.count(), 3);
+        assert!(provinces.same_province("宜宾", "成都"));
+        assert!(!provinces.same_province("成都", "深圳"));
+        assert!(!provinces.same_province("成都", "未知"));
+
+        provinces.connect("宜宾", "广州");
+        assert_eq!(provinces.count(), 2);
+        assert!(provinces.same_province("成都", "深圳"));
+        assert_eq!(
+            provinces.provinces(),
+            vec![
+                vec!["宜宾", "广州", "成都", "深圳"],
+                vec!["拉萨"],
+            ]
+        );
+
+        let data = parse_file(DEFAULT_DATA_FILE).unwrap();
+        let mut batch = Provinces::from_batch(&data["4"]);
+        assert!(batch.same_province("嘉定", "金山"));
+        assert_eq!(batch.provinces().len(), 2);
+    }
+
+    // 同一份数据的 JSON 与 CSV 版本应得到相同结果，且不依赖当前工作目录
+    #[test]
+    fn test_count_provinces_from_sources() {
+        let dir = env!("CARGO_MANIFEST_DIR");
+        assert_eq!(count_provinces_from(format!("{dir}/district.json")).unwrap(), TEST_CASE);
+        assert_eq!(count_provinces_from(format!("{dir}/district.csv")).unwrap(), TEST_CASE);
+        assert!(matches!(
+            count_provinces_from(format!("{dir}/missing.csv")),
+            Err(DistrictError::Io(_))
+        ));
+
+        let csv = "# 注释\n\n A , B , 1\nB,C,1\nD,E,1\nX,Y,2\n";
+        assert_eq!(count_provinces_from_reader(csv.as_bytes(), DataFormat::Csv).unwrap(), "2,1");
+        let json = r#"{"1": {"A": ["B"], "C": []}}"#;
+        assert_eq!(count_provinces_from_reader(json.as_bytes(), DataFormat::Json).unwrap(), "2");
+
+        let data = parse_csv_str(csv).unwrap();
+        assert_eq!(data["1"]["A"], ["B"]);
+        assert!(matches!(
+            parse_csv_str("A,B,1\nA,B\n"),
+            Err(DistrictError::Csv { line: 2, .. })
+        ));
+        assert!(matches!(
+            parse_csv_str("city_a,city_b,batch\nA,,1\n"),
+            Err(DistrictError::Csv { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_districts_builder() {
+        let data = DistrictsBuilder::new()
+            .connect("1", "成都", &["宜宾", "自贡"])
+            .connect("1", "深圳", &["广州"])
+            .city("1", "拉萨")
+            .connect("2", "北京", &["房山"])
+            .connect("2", "房山", &["北京"])
+            .build();
+
+        assert_eq!(data["1"]["成都"], ["宜宾", "自贡"]);
+        assert!(data["1"]["拉萨"].is_empty());
+        assert_eq!(count_provinces_in(&data), "3,1");
+    }
 }
@@ -0,0 +1,268 @@
+//! Proleptic Gregorian calendar arithmetic for any year
+//!
+//! Dates are converted to a day count relative to 1970-01-01 for differences
+//! and weekday calculations, so no year is special-cased.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Serialize, Serializer};
+
+/// Day of the week, numbered 1 (Monday) to 7 (Sunday) as in ISO 8601
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weekday {
+    Monday = 1,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    /// Returns the ISO 8601 weekday number (1 = Monday, 7 = Sunday)
+    pub fn number_from_monday(self) -> u32 {
+        self as u32
+    }
+
+    /// Returns true for Saturday and Sunday
+    pub fn is_weekend(self) -> bool {
+        matches!(self, Weekday::Saturday | Weekday::Sunday)
+    }
+
+    fn from_number_from_monday(n: u32) -> Weekday {
+        match n {
+            1 => Weekday::Monday,
+            2 => Weekday::Tuesday,
+            3 => Weekday::Wednesday,
+            4 => Weekday::Thursday,
+            5 => Weekday::Friday,
+            6 => Weekday::Saturday,
+            _ => Weekday::Sunday,
+        }
+    }
+}
+
+/// The reason a date was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateError {
+    /// The text is not a date in the format "YYYY-MM-DD"
+    InvalidFormat(String),
+    /// The month is not 1-12
+    MonthOutOfRange(u32),
+    /// The day does not exist in that month (e.g. February 29 of a common year)
+    DayOutOfRange { year: i32, month: u32, day: u32 },
+    /// The date is valid but outside the years a calculation has data for
+    YearNotSupported(i32),
+}
+
+impl fmt::Display for DateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DateError::InvalidFormat(s) => write!(f, "expected a date as YYYY-MM-DD, got {:?}", s),
+            DateError::MonthOutOfRange(month) => write!(f, "month out of range: {}", month),
+            DateError::DayOutOfRange { year, month, day } => {
+                write!(f, "day out of range: {}-{:02}-{:02}", year, month, day)
+            }
+            DateError::YearNotSupported(year) => write!(f, "year not supported: {}", year),
+        }
+    }
+}
+
+impl std::error::Error for DateError {}
+
+/// A calendar date in the proleptic Gregorian calendar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    /// Creates a date from its year, month and day, checking that it exists
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Result<Date, DateError> {
+        if !(1..=12).contains(&month) {
+            return Err(DateError::MonthOutOfRange(month));
+        }
+        if !(1..=days_in_month(year, month)).contains(&day) {
+            return Err(DateError::DayOutOfRange { year, month, day });
+        }
+        Ok(Date { year, month, day })
+    }
+
+    /// Creates a date from its year, month and day
+    ///
+    /// # Panics
+    /// Panics if the month is not 1-12 or the day does not exist in that month
+    pub fn new(year: i32, month: u32, day: u32) -> Date {
+        Date::from_ymd(year, month, day).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn year(self) -> i32 {
+        self.year
+    }
+
+    pub fn month(self) -> u32 {
+        self.month
+    }
+
+    pub fn day(self) -> u32 {
+        self.day
+    }
+
+    /// Returns the day of the year (1-365, or 1-366 in leap years)
+    pub fn day_of_year(self) -> u32 {
+        (1..self.month)
+            .map(|m| days_in_month(self.year, m))
+            .sum::<u32>()
+            + self.day
+    }
+
+    /// Returns the number of days left in the year after this date
+    pub fn days_remaining_in_year(self) -> u32 {
+        days_in_year(self.year) - self.day_of_year()
+    }
+
+    pub fn weekday(self) -> Weekday {
+        // 1970-01-01 was a Thursday (ISO weekday 4)
+        let n = (self.to_days() + 3).rem_euclid(7) as u32 + 1;
+        Weekday::from_number_from_monday(n)
+    }
+
+    /// Returns the ISO 8601 week-numbering year and week (1-53)
+    ///
+    /// Days at the start of January can belong to the last week of the previous
+    /// year, and days at the end of December to week 1 of the next year.
+    pub fn iso_week(self) -> (i32, u32) {
+        let ordinal = self.day_of_year() as i32;
+        let weekday = self.weekday().number_from_monday() as i32;
+        let week = (ordinal - weekday + 10) / 7;
+
+        if week < 1 {
+            (self.year - 1, iso_weeks_in_year(self.year - 1))
+        } else if week as u32 > iso_weeks_in_year(self.year) {
+            (self.year + 1, 1)
+        } else {
+            (self.year, week as u32)
+        }
+    }
+
+    /// Returns the number of days since 1970-01-01 (negative before it)
+    pub fn to_days(self) -> i64 {
+        // Shift the year to start in March so the leap day is the last day
+        let y = if self.month <= 2 {
+            self.year as i64 - 1
+        } else {
+            self.year as i64
+        };
+        let era = y.div_euclid(400);
+        let year_of_era = y - era * 400;
+        let m = self.month as i64;
+        let day_of_year = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// Creates the date that is `days` days after 1970-01-01
+    pub fn from_days(days: i64) -> Date {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (year_of_era + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+        Date { year, month, day }
+    }
+
+    /// Returns the date `days` days later (earlier if negative)
+    pub fn add_days(self, days: i64) -> Date {
+        Date::from_days(self.to_days() + days)
+    }
+
+    /// Returns the following day
+    pub fn succ(self) -> Date {
+        self.add_days(1)
+    }
+
+    /// Returns the signed number of days from this date to `other`
+    pub fn days_until(self, other: Date) -> i64 {
+        other.to_days() - self.to_days()
+    }
+}
+
+impl FromStr for Date {
+    type Err = DateError;
+
+    /// Parses a date in the format "YYYY-MM-DD" (four-digit year, two-digit month and day)
+    fn from_str(s: &str) -> Result<Date, DateError> {
+        let invalid = || DateError::InvalidFormat(s.to_string());
+        let fields: Vec<&str> = s.split('-').collect();
+        let [year, month, day] = fields.as_slice() else {
+            return Err(invalid());
+        };
+        let all_digits =
+            |field: &str, len| field.len() == len && field.bytes().all(|b| b.is_ascii_digit());
+        if !(all_digits(year, 4) && all_digits(month, 2) && all_digits(day, 2)) {
+            return Err(invalid());
+        }
+
+        let year = year.parse().map_err(|_| invalid())?;
+        let month = month.parse().map_err(|_| invalid())?;
+        let day = day.parse().map_err(|_| invalid())?;
+        Date::from_ymd(year, month, day)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Serializes as the "YYYY-MM-DD" string
+impl Serialize for Date {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Determines if a year is a leap year
+pub fn is_leap_year(year: i32) -> bool {
+    // Leap year rules: divisible by 4, but not by 100 unless also by 400
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Returns the number of days in a month (1-12) of the given year
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+pub fn days_in_year(year: i32) -> u32 {
+    if is_leap_year(year) {
+        366
+    } else {
+        365
+    }
+}
+
+/// Returns the number of ISO 8601 weeks (52 or 53) in a week-numbering year
+///
+/// A year has 53 weeks when it starts on a Thursday, or on a Wednesday in a
+/// leap year.
+pub fn iso_weeks_in_year(year: i32) -> u32 {
+    match Date::new(year, 1, 1).weekday() {
+        Weekday::Thursday => 53,
+        Weekday::Wednesday if is_leap_year(year) => 53,
+        _ => 52,
+    }
+}
@@ -0,0 +1,6 @@
+//! Calendar facts for a date: ISO-8601 weeks, the Spring Festival and A-share trading days
+
+pub mod calc_time;
+pub mod date;
+pub mod lunar;
+pub mod trading;
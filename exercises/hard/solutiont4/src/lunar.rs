@@ -0,0 +1,250 @@
+//! Chinese lunisolar calendar conversion for 1900-2100
+//!
+//! Each year is described by one entry of [`LUNAR_INFO`]; the conversion walks
+//! month lengths from lunar 1900-01-01, which fell on 1900-01-31.
+
+use crate::date::{Date, DateError};
+
+/// The first and last lunar years covered by the table
+pub const FIRST_YEAR: i32 = 1900;
+pub const LAST_YEAR: i32 = 2100;
+
+/// Month layout of lunar years 1900-2100
+///
+/// - bits 0-3: the leap month (0 if the year has none)
+/// - bits 4-15: months 12 down to 1, a set bit meaning a 30-day month
+/// - bit 16: set if the leap month has 30 days
+#[rustfmt::skip]
+const LUNAR_INFO: [u32; 201] = [
+    0x04bd8, 0x04ae0, 0x0a570, 0x054d5, 0x0d260, 0x0d950, 0x16554, 0x056a0, 0x09ad0, 0x055d2, // 1900-1909
+    0x04ae0, 0x0a5b6, 0x0a4d0, 0x0d250, 0x1d255, 0x0b540, 0x0d6a0, 0x0ada2, 0x095b0, 0x14977, // 1910-1919
+    0x04970, 0x0a4b0, 0x0b4b5, 0x06a50, 0x06d40, 0x1ab54, 0x02b60, 0x09570, 0x052f2, 0x04970, // 1920-1929
+    0x06566, 0x0d4a0, 0x0ea50, 0x16a95, 0x05ad0, 0x02b60, 0x186e3, 0x092e0, 0x1c8d7, 0x0c950, // 1930-1939
+    0x0d4a0, 0x1d8a6, 0x0b550, 0x056a0, 0x1a5b4, 0x025d0, 0x092d0, 0x0d2b2, 0x0a950, 0x0b557, // 1940-1949
+    0x06ca0, 0x0b550, 0x15355, 0x04da0, 0x0a5b0, 0x14573, 0x052b0, 0x0a9a8, 0x0e950, 0x06aa0, // 1950-1959
+    0x0aea6, 0x0ab50, 0x04b60, 0x0aae4, 0x0a570, 0x05260, 0x0f263, 0x0d950, 0x05b57, 0x056a0, // 1960-1969
+    0x096d0, 0x04dd5, 0x04ad0, 0x0a4d0, 0x0d4d4, 0x0d250, 0x0d558, 0x0b540, 0x0b6a0, 0x195a6, // 1970-1979
+    0x095b0, 0x049b0, 0x0a974, 0x0a4b0, 0x0b27a, 0x06a50, 0x06d40, 0x0af46, 0x0ab60, 0x09570, // 1980-1989
+    0x04af5, 0x04970, 0x064b0, 0x074a3, 0x0ea50, 0x06b58, 0x05ac0, 0x0ab60, 0x096d5, 0x092e0, // 1990-1999
+    0x0c960, 0x0d954, 0x0d4a0, 0x0da50, 0x07552, 0x056a0, 0x0abb7, 0x025d0, 0x092d0, 0x0cab5, // 2000-2009
+    0x0a950, 0x0b4a0, 0x0baa4, 0x0ad50, 0x055d9, 0x04ba0, 0x0a5b0, 0x15176, 0x052b0, 0x0a930, // 2010-2019
+    0x07954, 0x06aa0, 0x0ad50, 0x05b52, 0x04b60, 0x0a6e6, 0x0a4e0, 0x0d260, 0x0ea65, 0x0d530, // 2020-2029
+    0x05aa0, 0x076a3, 0x096d0, 0x04afb, 0x04ad0, 0x0a4d0, 0x1d0b6, 0x0d250, 0x0d520, 0x0dd45, // 2030-2039
+    0x0b5a0, 0x056d0, 0x055b2, 0x049b0, 0x0a577, 0x0a4b0, 0x0aa50, 0x1b255, 0x06d20, 0x0ada0, // 2040-2049
+    0x14b63, 0x09370, 0x049f8, 0x04970, 0x064b0, 0x168a6, 0x0ea50, 0x06b20, 0x1a6c4, 0x0aae0, // 2050-2059
+    0x092e0, 0x0d2e3, 0x0c960, 0x0d557, 0x0d4a0, 0x0da50, 0x05d55, 0x056a0, 0x0a6d0, 0x055d4, // 2060-2069
+    0x052d0, 0x0a9b8, 0x0a950, 0x0b4a0, 0x0b6a6, 0x0ad50, 0x055a0, 0x0aba4, 0x0a5b0, 0x052b0, // 2070-2079
+    0x0b273, 0x06930, 0x07337, 0x06aa0, 0x0ad50, 0x14b55, 0x04b60, 0x0a570, 0x054e4, 0x0d160, // 2080-2089
+    0x0e968, 0x0d520, 0x0daa0, 0x16aa6, 0x056d0, 0x04ae0, 0x0a9d4, 0x0a2d0, 0x0d150, 0x0f252, // 2090-2099
+    0x0d520, // 2100
+];
+
+/// A date in the Chinese lunar calendar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LunarDate {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+    /// True for days of the intercalary month that follows `month`
+    pub is_leap_month: bool,
+}
+
+/// Traditional holidays that fall on a fixed lunar date
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LunarHoliday {
+    /// 春节, 1st day of the 1st month
+    SpringFestival,
+    /// 元宵节, 15th day of the 1st month
+    LanternFestival,
+    /// 端午节, 5th day of the 5th month
+    DragonBoatFestival,
+    /// 七夕, 7th day of the 7th month
+    Qixi,
+    /// 中秋节, 15th day of the 8th month
+    MidAutumnFestival,
+    /// 重阳节, 9th day of the 9th month
+    DoubleNinthFestival,
+}
+
+impl LunarHoliday {
+    /// Returns the lunar month and day of the holiday
+    pub fn lunar_month_day(self) -> (u32, u32) {
+        match self {
+            LunarHoliday::SpringFestival => (1, 1),
+            LunarHoliday::LanternFestival => (1, 15),
+            LunarHoliday::DragonBoatFestival => (5, 5),
+            LunarHoliday::Qixi => (7, 7),
+            LunarHoliday::MidAutumnFestival => (8, 15),
+            LunarHoliday::DoubleNinthFestival => (9, 9),
+        }
+    }
+
+    /// Returns the Gregorian date of the holiday in the given lunar year
+    ///
+    /// # Returns
+    /// None if the year is outside 1900-2100
+    pub fn date(self, year: i32) -> Option<Date> {
+        let (month, day) = self.lunar_month_day();
+        to_solar(LunarDate {
+            year,
+            month,
+            day,
+            is_leap_month: false,
+        })
+    }
+}
+
+/// Returns the Gregorian date of the Spring Festival (lunar new year) of a year
+///
+/// # Returns
+/// None if the year is outside 1900-2100
+pub fn spring_festival(year: i32) -> Option<Date> {
+    LunarHoliday::SpringFestival.date(year)
+}
+
+/// Returns the first Spring Festival on or after the given date
+///
+/// # Returns
+/// `YearNotSupported` with the year of that Spring Festival if it is not
+/// covered by the table
+pub fn next_spring_festival(date: Date) -> Result<Date, DateError> {
+    let this_year =
+        spring_festival(date.year()).ok_or(DateError::YearNotSupported(date.year()))?;
+    if this_year >= date {
+        return Ok(this_year);
+    }
+    let year = date.year() + 1;
+    spring_festival(year).ok_or(DateError::YearNotSupported(year))
+}
+
+/// Returns the leap month of a lunar year, if it has one
+pub fn leap_month(year: i32) -> Option<u32> {
+    match info(year)? & 0xf {
+        0 => None,
+        month => Some(month),
+    }
+}
+
+/// Returns the number of days (29 or 30) in a month of a lunar year
+///
+/// # Arguments
+/// * `year` - Lunar year
+/// * `month` - Lunar month (1-12)
+/// * `is_leap_month` - Whether to look at the intercalary month after `month`
+pub fn month_days(year: i32, month: u32, is_leap_month: bool) -> Option<u32> {
+    let info = info(year)?;
+    if !(1..=12).contains(&month) {
+        return None;
+    }
+
+    let big = if is_leap_month {
+        if info & 0xf != month {
+            return None;
+        }
+        info & 0x10000 != 0
+    } else {
+        info & (0x10000 >> month) != 0
+    };
+    Some(if big { 30 } else { 29 })
+}
+
+/// Returns the number of days in a lunar year, including any leap month
+pub fn year_days(year: i32) -> Option<u32> {
+    let regular: u32 = (1..=12)
+        .map(|month| month_days(year, month, false))
+        .sum::<Option<u32>>()?;
+    let leap = match leap_month(year) {
+        Some(month) => month_days(year, month, true)?,
+        None => 0,
+    };
+    Some(regular + leap)
+}
+
+/// Converts a lunar date to the Gregorian calendar
+///
+/// # Returns
+/// None if the year is outside 1900-2100 or the lunar date does not exist
+pub fn to_solar(lunar: LunarDate) -> Option<Date> {
+    if lunar.day < 1 || lunar.day > month_days(lunar.year, lunar.month, lunar.is_leap_month)? {
+        return None;
+    }
+
+    let mut offset: i64 = (FIRST_YEAR..lunar.year)
+        .map(|y| year_days(y).map(i64::from))
+        .sum::<Option<i64>>()?;
+    let leap = leap_month(lunar.year);
+    for month in 1..lunar.month {
+        offset += i64::from(month_days(lunar.year, month, false)?);
+        if leap == Some(month) {
+            offset += i64::from(month_days(lunar.year, month, true)?);
+        }
+    }
+    if lunar.is_leap_month {
+        offset += i64::from(month_days(lunar.year, lunar.month, false)?);
+    }
+    offset += i64::from(lunar.day - 1);
+
+    Some(epoch().add_days(offset))
+}
+
+/// Converts a Gregorian date to the lunar calendar
+///
+/// # Returns
+/// None if the date is before 1900-01-31 or after the end of lunar year 2100
+pub fn from_solar(date: Date) -> Option<LunarDate> {
+    let mut offset = epoch().days_until(date);
+    if offset < 0 {
+        return None;
+    }
+
+    let mut year = FIRST_YEAR;
+    loop {
+        let days = i64::from(year_days(year)?);
+        if offset < days {
+            break;
+        }
+        offset -= days;
+        year += 1;
+    }
+
+    let leap = leap_month(year);
+    for month in 1..=12 {
+        let days = i64::from(month_days(year, month, false)?);
+        if offset < days {
+            return Some(LunarDate {
+                year,
+                month,
+                day: offset as u32 + 1,
+                is_leap_month: false,
+            });
+        }
+        offset -= days;
+
+        if leap == Some(month) {
+            let days = i64::from(month_days(year, month, true)?);
+            if offset < days {
+                return Some(LunarDate {
+                    year,
+                    month,
+                    day: offset as u32 + 1,
+                    is_leap_month: true,
+                });
+            }
+            offset -= days;
+        }
+    }
+    None
+}
+
+/// Lunar 1900-01-01
+fn epoch() -> Date {
+    Date::new(1900, 1, 31)
+}
+
+fn info(year: i32) -> Option<u32> {
+    if (FIRST_YEAR..=LAST_YEAR).contains(&year) {
+        Some(LUNAR_INFO[(year - FIRST_YEAR) as usize])
+    } else {
+        None
+    }
+}
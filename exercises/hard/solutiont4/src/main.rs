@@ -1,7 +1,5 @@
 //I AM NOT DONE 
 //Calculated according to ISO8061 standard
 
-mod calc_time;
-
 fn main() {
 }
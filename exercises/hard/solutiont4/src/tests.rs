@@ -1,10 +1,19 @@
 // src/tests.rs
 mod calc_time;
+mod date;
+mod lunar;
+mod trading;
+#[path = "../../common/time_budget.rs"]
+mod time_budget;
 
 #[cfg(test)]
 mod tests {
-    use super::calc_time::time_info;
+    use super::calc_time::{parse_time_info, time_info, time_info_range, try_time_info, TimeInfo};
+    use super::date::{days_in_month, iso_weeks_in_year, Date, DateError, Weekday};
+    use super::lunar::{self, LunarDate, LunarHoliday};
+    use super::trading::{self, CalendarError, TradingCalendar, DEFAULT_CALENDAR_FILE};
     use std::time::{Instant, Duration};
+    use super::time_budget::time_budget;
 
     
     const TEST_CASES: &[(&str, &str)] = &[
@@ -39,7 +48,7 @@ mod tests {
             let duration = start.elapsed();
 
             // 时间超0.2s，判定不合格
-            if duration <= Duration::from_millis(200) && result == *expected {
+            if duration <= time_budget(Duration::from_millis(200)) && result == *expected {
                 total_score += 10.0;
             }
         }
@@ -47,4 +56,237 @@ mod tests {
         println!("Total score: {:.2}", total_score);
         assert_eq!(100.00, total_score);
     }
+
+    // ISO 8601 周数：年初可能属于上一年的最后一周，年末可能属于下一年的第 1 周
+    const ISO_WEEK_CASES: &[(i32, u32, u32, i32, u32)] = &[
+        (2020, 12, 31, 2020, 53),
+        (2021, 1, 1, 2020, 53),
+        (2021, 1, 3, 2020, 53),
+        (2021, 1, 4, 2021, 1),
+        (2024, 12, 30, 2025, 1),
+        (2025, 12, 31, 2026, 1),
+        (2026, 12, 31, 2026, 53),
+        (2027, 1, 1, 2026, 53),
+        (2032, 12, 31, 2032, 53),
+        (2000, 1, 1, 1999, 52),
+    ];
+
+    #[test]
+    fn test_iso_week() {
+        for &(y, m, d, week_year, week) in ISO_WEEK_CASES {
+            assert_eq!(Date::new(y, m, d).iso_week(), (week_year, week), "{y}-{m}-{d}");
+        }
+        assert_eq!(iso_weeks_in_year(2020), 53);
+        assert_eq!(iso_weeks_in_year(2025), 52);
+    }
+
+    #[test]
+    fn test_general_year_calendar() {
+        assert_eq!(Date::new(2000, 1, 1).weekday(), Weekday::Saturday);
+        assert_eq!(Date::new(2024, 2, 29).weekday(), Weekday::Thursday);
+        assert_eq!(Date::new(1900, 3, 1).weekday(), Weekday::Thursday);
+        assert_eq!(Date::new(2100, 12, 31).weekday().number_from_monday(), 5);
+
+        assert_eq!(days_in_month(2024, 2), 29);
+        assert_eq!(days_in_month(1900, 2), 28);
+        assert_eq!(days_in_month(2000, 2), 29);
+        assert_eq!(Date::new(2024, 12, 31).day_of_year(), 366);
+        assert_eq!(Date::new(2024, 3, 1).day_of_year(), 61);
+        assert_eq!(Date::new(2024, 3, 1).days_remaining_in_year(), 305);
+
+        let date = Date::new(2024, 2, 28);
+        assert_eq!(date.succ(), Date::new(2024, 2, 29));
+        assert_eq!(date.add_days(2), Date::new(2024, 3, 1));
+        assert_eq!(date.add_days(-59), Date::new(2023, 12, 31));
+        assert_eq!(Date::new(1970, 1, 1).to_days(), 0);
+        assert_eq!(Date::from_days(-1), Date::new(1969, 12, 31));
+        assert_eq!(Date::new(2000, 1, 1).days_until(Date::new(2100, 1, 1)), 36525);
+    }
+
+    // 已知的春节公历日期（年, 月, 日）
+    const SPRING_FESTIVALS: &[(i32, u32, u32)] = &[
+        (1900, 1, 31),
+        (1949, 1, 29),
+        (1990, 1, 27),
+        (2000, 2, 5),
+        (2020, 1, 25),
+        (2021, 2, 12),
+        (2022, 2, 1),
+        (2023, 1, 22),
+        (2024, 2, 10),
+        (2025, 1, 29),
+        (2026, 2, 17),
+        (2027, 2, 6),
+        (2028, 1, 26),
+        (2050, 1, 23),
+        (2100, 2, 9),
+    ];
+
+    #[test]
+    fn test_lunar_calendar() {
+        for &(y, m, d) in SPRING_FESTIVALS {
+            assert_eq!(lunar::spring_festival(y), Some(Date::new(y, m, d)), "{y}");
+        }
+        assert_eq!(lunar::spring_festival(1899), None);
+        assert_eq!(lunar::spring_festival(2101), None);
+
+        assert_eq!(LunarHoliday::MidAutumnFestival.date(2024), Some(Date::new(2024, 9, 17)));
+        assert_eq!(LunarHoliday::MidAutumnFestival.date(2025), Some(Date::new(2025, 10, 6)));
+        assert_eq!(LunarHoliday::DragonBoatFestival.date(2025), Some(Date::new(2025, 5, 31)));
+        assert_eq!(LunarHoliday::LanternFestival.date(2025), Some(Date::new(2025, 2, 12)));
+        assert_eq!(LunarHoliday::Qixi.date(2025), Some(Date::new(2025, 8, 29)));
+        assert_eq!(LunarHoliday::DoubleNinthFestival.date(2025), Some(Date::new(2025, 10, 29)));
+
+        // 2023 年闰二月，2025 年闰六月
+        assert_eq!(lunar::leap_month(2023), Some(2));
+        assert_eq!(lunar::leap_month(2025), Some(6));
+        assert_eq!(lunar::leap_month(2024), None);
+        assert_eq!(
+            lunar::from_solar(Date::new(2025, 7, 25)),
+            Some(LunarDate { year: 2025, month: 6, day: 1, is_leap_month: true })
+        );
+
+        // 公历与农历往返转换保持不变
+        let mut date = Date::new(1900, 1, 31);
+        while date.year() < 2100 {
+            let lunar_date = lunar::from_solar(date).unwrap();
+            assert_eq!(lunar::to_solar(lunar_date), Some(date));
+            date = date.add_days(17);
+        }
+
+        assert_eq!(time_info("2025-01-29"), "5,3,29,336,0,6");
+        assert_eq!(time_info("2027-01-01"), "53,5,1,364,36,2");
+    }
+
+    #[test]
+    fn test_trading_calendar() {
+        let sse = TradingCalendar::builtin("SSE").unwrap();
+        assert_eq!(sse.years().collect::<Vec<_>>(), vec![2025]);
+
+        // 春节假期与调休：2025-02-08 周六上班，但交易所不开市
+        assert!(!sse.is_trading_day(Date::new(2025, 1, 28)));
+        assert!(!sse.is_trading_day(Date::new(2025, 2, 4)));
+        assert!(sse.is_trading_day(Date::new(2025, 2, 5)));
+        assert!(sse.is_makeup_day(Date::new(2025, 2, 8)));
+        assert!(sse.is_working_day(Date::new(2025, 2, 8)));
+        assert!(!sse.is_trading_day(Date::new(2025, 2, 8)));
+        assert!(!sse.is_working_day(Date::new(2025, 2, 9)));
+
+        assert_eq!(sse.next_trading_day(Date::new(2025, 9, 30)), Date::new(2025, 10, 9));
+        assert_eq!(sse.prev_trading_day(Date::new(2025, 10, 9)), Date::new(2025, 9, 30));
+        assert_eq!(sse.prev_trading_day(Date::new(2025, 6, 3)), Date::new(2025, 5, 30));
+        assert_eq!(sse.next_trading_day(Date::new(2025, 4, 3)), Date::new(2025, 4, 7));
+        // 没有全年安排的年份只休周末和每年固定的元旦
+        assert_eq!(sse.next_trading_day(Date::new(2026, 12, 31)), Date::new(2027, 1, 4));
+
+        // 2025 年 A 股共 243 个交易日
+        let days = sse.trading_days_between(Date::new(2025, 1, 1), Date::new(2026, 1, 1));
+        assert_eq!(days, 243);
+        assert_eq!(sse.trading_days_between(Date::new(2025, 2, 5), Date::new(2025, 2, 5)), 0);
+
+        let szse = TradingCalendar::from_file(DEFAULT_CALENDAR_FILE, "SZSE").unwrap();
+        assert_eq!(&szse, TradingCalendar::builtin("SZSE").unwrap());
+        assert!(matches!(
+            TradingCalendar::from_file(DEFAULT_CALENDAR_FILE, "HKEX"),
+            Err(CalendarError::UnknownExchange(_))
+        ));
+
+        let mut custom = TradingCalendar::new();
+        custom.add_holiday(Date::new(2030, 1, 1), Date::new(2030, 1, 1));
+        assert_eq!(custom.next_trading_day(Date::new(2029, 12, 31)), Date::new(2030, 1, 2));
+
+        let calendars = trading::parse_str("[XSHG 2030]\nholiday 2030-01-01 2030-01-02\n").unwrap();
+        assert!(!calendars["XSHG"].is_trading_day(Date::new(2030, 1, 2)));
+        for (data, line) in [
+            ("holiday 2025-01-01", 1),
+            ("[SSE]\nholiday 2025-02-30", 2),
+            ("[SSE]\n\nholiday 2025-02-03 2025-02-01", 3),
+            ("[SSE]\nannual 13-01", 2),
+            ("[SSE 2025\n", 1),
+            ("[SSE]\nclosed 2025-01-01", 2),
+        ] {
+            match trading::parse_str(data) {
+                Err(CalendarError::Syntax { line: l, .. }) => assert_eq!(l, line, "{data:?}"),
+                other => panic!("{data:?} parsed as {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_date_parsing() {
+        assert_eq!("2024-02-29".parse(), Ok(Date::new(2024, 2, 29)));
+        assert_eq!(Date::new(2025, 1, 5).to_string(), "2025-01-05");
+
+        for input in ["2025/01/01", "2025-1-1", "20250101", "2025-01-01-01", " 2025-01-01", "abcd-01-01", ""] {
+            assert_eq!(
+                input.parse::<Date>(),
+                Err(DateError::InvalidFormat(input.to_string())),
+                "{input:?}"
+            );
+        }
+        assert_eq!("2025-13-40".parse::<Date>(), Err(DateError::MonthOutOfRange(13)));
+        assert_eq!("2025-00-01".parse::<Date>(), Err(DateError::MonthOutOfRange(0)));
+        assert_eq!(
+            "2025-02-29".parse::<Date>(),
+            Err(DateError::DayOutOfRange { year: 2025, month: 2, day: 29 })
+        );
+        assert_eq!(
+            Date::from_ymd(1900, 2, 29),
+            Err(DateError::DayOutOfRange { year: 1900, month: 2, day: 29 })
+        );
+        assert_eq!(
+            "2025-04-31".parse::<Date>(),
+            Err(DateError::DayOutOfRange { year: 2025, month: 4, day: 31 })
+        );
+
+        // 可失败版本返回错误而不是 panic
+        assert_eq!(try_time_info("2025-01-01"), Ok("1,3,1,364,28,0".to_string()));
+        assert_eq!(try_time_info("2025-13-40"), Err(DateError::MonthOutOfRange(13)));
+        assert!(matches!(try_time_info("2025/01/01"), Err(DateError::InvalidFormat(_))));
+        assert_eq!(try_time_info("2101-03-01"), Err(DateError::YearNotSupported(2101)));
+        // 2100 年春节已过，需要的是表外 2101 年的春节
+        assert_eq!(try_time_info("2100-12-31"), Err(DateError::YearNotSupported(2101)));
+    }
+
+    #[test]
+    fn test_time_info_struct() {
+        let info = parse_time_info("2025-12-31").unwrap();
+        assert_eq!(
+            info,
+            TimeInfo {
+                date: Date::new(2025, 12, 31),
+                iso_year: 2026,
+                week: 1,
+                weekday: 3,
+                day_of_year: 365,
+                days_remaining: 0,
+                days_to_spring_festival: 48,
+                days_to_next_trading_day: 1,
+            }
+        );
+        // Display 保持原来的逗号分隔格式
+        assert_eq!(info.to_string(), "1,3,365,0,48,1");
+        for (input, expected) in TEST_CASES {
+            assert_eq!(parse_time_info(input).unwrap().to_string(), *expected);
+        }
+
+        let json = serde_json::to_value(info).unwrap();
+        assert_eq!(json["date"], "2025-12-31");
+        assert_eq!(json["iso_year"], 2026);
+        assert_eq!(json["days_to_spring_festival"], 48);
+
+        // 批量计算：2025-01-27 至 2025-02-05 跨越春节休市
+        let range = time_info_range(Date::new(2025, 1, 27), Date::new(2025, 2, 5)).unwrap();
+        assert_eq!(range.len(), 10);
+        assert_eq!(range[0].date, Date::new(2025, 1, 27));
+        assert_eq!(range[0].days_to_next_trading_day, 8);
+        assert_eq!(range[2].days_to_spring_festival, 0);
+        assert_eq!(range[9].date, Date::new(2025, 2, 5));
+        assert!(range.windows(2).all(|w| w[0].date.succ() == w[1].date));
+        assert!(time_info_range(Date::new(2025, 2, 5), Date::new(2025, 2, 4)).unwrap().is_empty());
+        assert_eq!(
+            time_info_range(Date::new(2100, 12, 30), Date::new(2101, 1, 5)),
+            Err(DateError::YearNotSupported(2101))
+        );
+    }
 }
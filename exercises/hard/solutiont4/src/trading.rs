@@ -0,0 +1,254 @@
+//! Exchange trading calendars loaded from holiday data files
+//!
+//! A day is a trading day when it is a weekday and not an exchange holiday.
+//! Weekend make-up working days (调休) are recorded too, but the exchanges stay
+//! closed on them, so they only affect [`TradingCalendar::is_working_day`].
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs, io,
+    path::Path,
+    sync::OnceLock,
+};
+
+use crate::date::{days_in_month, Date};
+
+/// The calendar data shipped with the crate
+pub const DEFAULT_CALENDAR_FILE: &str =
+    concat!(env!("CARGO_MANIFEST_DIR"), "/trading_calendar.txt");
+
+/// The exchange whose calendar `time_info` uses
+pub const DEFAULT_EXCHANGE: &str = "SSE";
+
+const BUILTIN_DATA: &str = include_str!("../trading_calendar.txt");
+
+/// Error produced when reading calendar data
+#[derive(Debug)]
+pub enum CalendarError {
+    /// The file could not be read
+    Io(io::Error),
+    /// A line does not follow the calendar format
+    Syntax { line: usize, expected: &'static str },
+    /// The data has no section for the requested exchange
+    UnknownExchange(String),
+}
+
+impl fmt::Display for CalendarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalendarError::Io(e) => write!(f, "failed to read calendar data: {}", e),
+            CalendarError::Syntax { line, expected } => {
+                write!(f, "expected {} on calendar line {}", expected, line)
+            }
+            CalendarError::UnknownExchange(name) => write!(f, "no calendar for exchange {}", name),
+        }
+    }
+}
+
+impl std::error::Error for CalendarError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CalendarError::Io(e) => Some(e),
+            CalendarError::Syntax { .. } | CalendarError::UnknownExchange(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for CalendarError {
+    fn from(e: io::Error) -> Self {
+        CalendarError::Io(e)
+    }
+}
+
+/// The holidays and make-up working days of one exchange
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TradingCalendar {
+    /// (month, day) pairs closed every year
+    annual: BTreeSet<(u32, u32)>,
+    holidays: BTreeSet<Date>,
+    makeup_days: BTreeSet<Date>,
+    years: BTreeSet<i32>,
+}
+
+impl TradingCalendar {
+    /// Creates a calendar on which every weekday is a trading day
+    pub fn new() -> Self {
+        TradingCalendar::default()
+    }
+
+    /// Returns the calendar of an exchange from the data shipped with the crate
+    pub fn builtin(exchange: &str) -> Option<&'static TradingCalendar> {
+        static CALENDARS: OnceLock<BTreeMap<String, TradingCalendar>> = OnceLock::new();
+        CALENDARS
+            .get_or_init(|| parse_str(BUILTIN_DATA).expect("built-in trading calendar is valid"))
+            .get(exchange)
+    }
+
+    /// Reads the calendar of one exchange from a data file
+    ///
+    /// # Arguments
+    /// * `path` - Path to a file in the format of `trading_calendar.txt`
+    /// * `exchange` - Exchange code, e.g. "SSE"
+    pub fn from_file(path: impl AsRef<Path>, exchange: &str) -> Result<Self, CalendarError> {
+        let content = fs::read_to_string(path)?;
+        parse_str(&content)?
+            .remove(exchange)
+            .ok_or_else(|| CalendarError::UnknownExchange(exchange.to_string()))
+    }
+
+    /// Closes the exchange on a month and day of every year
+    pub fn add_annual_holiday(&mut self, month: u32, day: u32) {
+        self.annual.insert((month, day));
+    }
+
+    /// Closes the exchange from `start` to `end` inclusive
+    pub fn add_holiday(&mut self, start: Date, end: Date) {
+        let mut date = start;
+        while date <= end {
+            self.holidays.insert(date);
+            date = date.succ();
+        }
+    }
+
+    /// Records a weekend make-up working day
+    pub fn add_makeup_day(&mut self, date: Date) {
+        self.makeup_days.insert(date);
+    }
+
+    /// Marks a year as having its full schedule in the calendar
+    pub fn add_year(&mut self, year: i32) {
+        self.years.insert(year);
+    }
+
+    /// The years whose published schedule is in the calendar
+    pub fn years(&self) -> impl Iterator<Item = i32> + '_ {
+        self.years.iter().copied()
+    }
+
+    pub fn is_holiday(&self, date: Date) -> bool {
+        self.holidays.contains(&date) || self.annual.contains(&(date.month(), date.day()))
+    }
+
+    pub fn is_makeup_day(&self, date: Date) -> bool {
+        self.makeup_days.contains(&date)
+    }
+
+    /// Checks whether offices work on a date: a non-holiday weekday or a make-up day
+    pub fn is_working_day(&self, date: Date) -> bool {
+        self.is_makeup_day(date) || self.is_trading_day(date)
+    }
+
+    /// Checks whether the exchange is open on a date
+    pub fn is_trading_day(&self, date: Date) -> bool {
+        !date.weekday().is_weekend() && !self.is_holiday(date)
+    }
+
+    /// Returns the first trading day after `date`
+    pub fn next_trading_day(&self, date: Date) -> Date {
+        let mut date = date.succ();
+        while !self.is_trading_day(date) {
+            date = date.succ();
+        }
+        date
+    }
+
+    /// Returns the last trading day before `date`
+    pub fn prev_trading_day(&self, date: Date) -> Date {
+        let mut date = date.add_days(-1);
+        while !self.is_trading_day(date) {
+            date = date.add_days(-1);
+        }
+        date
+    }
+
+    /// Counts the trading days from `start` (inclusive) to `end` (exclusive)
+    ///
+    /// # Returns
+    /// 0 if `end` is not after `start`
+    pub fn trading_days_between(&self, start: Date, end: Date) -> usize {
+        let mut count = 0;
+        let mut date = start;
+        while date < end {
+            if self.is_trading_day(date) {
+                count += 1;
+            }
+            date = date.succ();
+        }
+        count
+    }
+}
+
+/// Parses calendar data into one calendar per exchange
+///
+/// Blank lines and text after `#` are ignored. Entries belong to the most
+/// recent `[EXCHANGE]` or `[EXCHANGE YEAR]` section header.
+pub fn parse_str(content: &str) -> Result<BTreeMap<String, TradingCalendar>, CalendarError> {
+    let mut calendars: BTreeMap<String, TradingCalendar> = BTreeMap::new();
+    let mut current: Option<String> = None;
+
+    for (index, raw) in content.lines().enumerate() {
+        let line = index + 1;
+        let syntax = |expected| CalendarError::Syntax { line, expected };
+        let text = raw.split('#').next().unwrap_or("").trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = text.strip_prefix('[') {
+            let header = header
+                .strip_suffix(']')
+                .ok_or(syntax("a closing ] after the section name"))?;
+            let mut fields = header.split_whitespace();
+            let exchange = fields.next().ok_or(syntax("an exchange name"))?;
+            let calendar = calendars.entry(exchange.to_string()).or_default();
+            if let Some(year) = fields.next() {
+                calendar.add_year(year.parse().map_err(|_| syntax("a year"))?);
+            }
+            if fields.next().is_some() {
+                return Err(syntax("[EXCHANGE] or [EXCHANGE YEAR]"));
+            }
+            current = Some(exchange.to_string());
+            continue;
+        }
+
+        let calendar = current
+            .as_ref()
+            .and_then(|exchange| calendars.get_mut(exchange))
+            .ok_or(syntax("a section header before the first entry"))?;
+        let fields: Vec<&str> = text.split_whitespace().collect();
+        match fields.as_slice() {
+            ["annual", month_day] => {
+                let (month, day) = parse_month_day(month_day).ok_or(syntax("a date as MM-DD"))?;
+                calendar.add_annual_holiday(month, day);
+            }
+            ["holiday", start, rest @ ..] if rest.len() <= 1 => {
+                let start: Date = start.parse().map_err(|_| syntax("a date as YYYY-MM-DD"))?;
+                let end = match rest {
+                    [end] => end.parse().map_err(|_| syntax("a date as YYYY-MM-DD"))?,
+                    _ => start,
+                };
+                if end < start {
+                    return Err(syntax("a holiday that ends after it starts"));
+                }
+                calendar.add_holiday(start, end);
+            }
+            ["makeup", date] => {
+                let date: Date = date.parse().map_err(|_| syntax("a date as YYYY-MM-DD"))?;
+                calendar.add_makeup_day(date);
+            }
+            _ => return Err(syntax("an annual, holiday or makeup entry")),
+        }
+    }
+    Ok(calendars)
+}
+
+fn parse_month_day(s: &str) -> Option<(u32, u32)> {
+    let (month, day) = s.split_once('-')?;
+    let (month, day) = (month.parse().ok()?, day.parse().ok()?);
+    // February 29 is allowed; it only matches in leap years
+    if (1..=12).contains(&month) && (1..=days_in_month(2000, month)).contains(&day) {
+        Some((month, day))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,3 @@
+//! Retirement dates under the 2025 gradual retirement-age reform
+
+pub mod retirement;
@@ -1,3 +1,308 @@
-pub fn retire_time(time: &str, tp: &str) -> String {
-    todo!()
+use std::{fmt, str::FromStr};
+
+/// The reason a retirement query was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RetirementError {
+    /// The birth month is not in the format "YYYY-MM"
+    InvalidMonth(String),
+    /// The worker category is not one of the known labels or aliases
+    UnknownCategory(String),
+}
+
+impl fmt::Display for RetirementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RetirementError::InvalidMonth(s) => {
+                write!(f, "expected a month as YYYY-MM, got {:?}", s)
+            }
+            RetirementError::UnknownCategory(s) => write!(
+                f,
+                "unknown worker category {:?}, expected one of: {}",
+                s,
+                POLICIES
+                    .iter()
+                    .map(|policy| policy.label)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RetirementError {}
+
+/// A calendar month
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct YearMonth {
+    pub year: i32,
+    /// 1-12
+    pub month: u32,
+}
+
+impl YearMonth {
+    /// Creates a month, returning None if `month` is not 1-12
+    pub fn new(year: i32, month: u32) -> Option<YearMonth> {
+        (1..=12)
+            .contains(&month)
+            .then_some(YearMonth { year, month })
+    }
+
+    /// Returns the month count since January of year 0
+    pub fn to_index(self) -> i64 {
+        i64::from(self.year) * 12 + i64::from(self.month) - 1
+    }
+
+    /// Creates a month from a count since January of year 0
+    pub fn from_index(index: i64) -> YearMonth {
+        YearMonth {
+            year: index.div_euclid(12) as i32,
+            month: index.rem_euclid(12) as u32 + 1,
+        }
+    }
+
+    /// Returns the month `months` months later
+    pub fn add_months(self, months: i64) -> YearMonth {
+        YearMonth::from_index(self.to_index() + months)
+    }
+}
+
+impl FromStr for YearMonth {
+    type Err = RetirementError;
+
+    fn from_str(s: &str) -> Result<YearMonth, RetirementError> {
+        let invalid = || RetirementError::InvalidMonth(s.to_string());
+        let (year, month) = s.split_once('-').ok_or_else(invalid)?;
+        let year = year.parse().map_err(|_| invalid())?;
+        let month = month.parse().map_err(|_| invalid())?;
+        YearMonth::new(year, month).ok_or_else(invalid)
+    }
+}
+
+impl fmt::Display for YearMonth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{:02}", self.year, self.month)
+    }
+}
+
+/// The worker categories covered by the retirement regulations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WorkerCategory {
+    /// 男职工
+    Male,
+    /// 原法定退休年龄55周岁女职工
+    Female55,
+    /// 原法定退休年龄50周岁女职工
+    Female50,
+}
+
+impl WorkerCategory {
+    /// Every category, in the order of [`POLICIES`]
+    pub const ALL: [WorkerCategory; 3] = [
+        WorkerCategory::Male,
+        WorkerCategory::Female55,
+        WorkerCategory::Female50,
+    ];
+
+    /// The category name used in the regulations
+    pub fn label(self) -> &'static str {
+        self.policy().label
+    }
+
+    /// Returns the reform policy of this category
+    pub fn policy(self) -> &'static RetirementPolicy {
+        match self {
+            WorkerCategory::Male => &MALE,
+            WorkerCategory::Female55 => &FEMALE_55,
+            WorkerCategory::Female50 => &FEMALE_50,
+        }
+    }
+}
+
+impl FromStr for WorkerCategory {
+    type Err = RetirementError;
+
+    /// Accepts the Chinese label or an English alias such as "male" or "female55"
+    fn from_str(s: &str) -> Result<WorkerCategory, RetirementError> {
+        let s = s.trim();
+        WorkerCategory::ALL
+            .into_iter()
+            .find(|category| category.policy().matches(s))
+            .ok_or_else(|| RetirementError::UnknownCategory(s.to_string()))
+    }
+}
+
+impl fmt::Display for WorkerCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// A gradual retirement-age reform for one category of workers
+///
+/// Workers whose original retirement month is before `start` keep the
+/// original age. From `start` on, every `cohort_months` months of original
+/// retirement dates push retirement back by one more month, up to
+/// `max_delay_months`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetirementPolicy {
+    /// The category name used in the regulations
+    pub label: &'static str,
+    /// English names also accepted for the category, compared
+    /// case-insensitively
+    pub aliases: &'static [&'static str],
+    /// Statutory retirement age before the reform, in years
+    pub original_age: u32,
+    /// First original retirement month affected by the reform
+    pub start: YearMonth,
+    /// Months of original retirement dates per month of delay
+    pub cohort_months: u32,
+    /// Final delay once the reform is fully phased in
+    pub max_delay_months: u32,
+}
+
+/// The 2025 reform: men 60 -> 63, women 55 -> 58 and 50 -> 55
+///
+/// A new category takes another row here and a variant of [`WorkerCategory`].
+pub const POLICIES: &[RetirementPolicy] = &[MALE, FEMALE_55, FEMALE_50];
+
+const MALE: RetirementPolicy = RetirementPolicy {
+    label: "男职工",
+    aliases: &["male", "man", "men"],
+    original_age: 60,
+    start: YearMonth {
+        year: 2025,
+        month: 1,
+    },
+    cohort_months: 4,
+    max_delay_months: 36,
+};
+
+const FEMALE_55: RetirementPolicy = RetirementPolicy {
+    label: "原法定退休年龄55周岁女职工",
+    aliases: &["female55", "female-55", "woman55", "women55"],
+    original_age: 55,
+    start: YearMonth {
+        year: 2025,
+        month: 1,
+    },
+    cohort_months: 4,
+    max_delay_months: 36,
+};
+
+const FEMALE_50: RetirementPolicy = RetirementPolicy {
+    label: "原法定退休年龄50周岁女职工",
+    aliases: &["female50", "female-50", "woman50", "women50"],
+    original_age: 50,
+    start: YearMonth {
+        year: 2025,
+        month: 1,
+    },
+    cohort_months: 2,
+    max_delay_months: 60,
+};
+
+impl RetirementPolicy {
+    /// Looks up the policy of a category in [`POLICIES`] by its label or an
+    /// alias
+    pub fn find(category: &str) -> Option<&'static RetirementPolicy> {
+        let category = category.trim();
+        POLICIES.iter().find(|policy| policy.matches(category))
+    }
+
+    fn matches(&self, category: &str) -> bool {
+        self.label == category
+            || self
+                .aliases
+                .iter()
+                .any(|alias| alias.eq_ignore_ascii_case(category))
+    }
+
+    /// Calculates the months of delay for a birth month
+    pub fn delay_months(&self, birth: YearMonth) -> u32 {
+        let original = birth.to_index() + i64::from(self.original_age) * 12;
+        let start = self.start.to_index();
+        if original < start {
+            return 0;
+        }
+        let steps = (original - start) / i64::from(self.cohort_months) + 1;
+        steps.min(i64::from(self.max_delay_months)) as u32
+    }
+
+    /// Calculates the retirement plan of a worker born in `birth`
+    pub fn plan(&self, birth: YearMonth) -> RetirementPlan {
+        let delay_months = self.delay_months(birth);
+        let age_in_months = self.original_age * 12 + delay_months;
+        RetirementPlan {
+            retirement: birth.add_months(i64::from(age_in_months)),
+            age_years: age_in_months / 12,
+            age_months: age_in_months % 12,
+            delay_months,
+        }
+    }
+}
+
+/// When a worker retires under the reform
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetirementPlan {
+    pub retirement: YearMonth,
+    /// Whole years of the retirement age
+    pub age_years: u32,
+    /// Months of the retirement age beyond `age_years` (0-11)
+    pub age_months: u32,
+    /// Months added to the original retirement age
+    pub delay_months: u32,
+}
+
+impl RetirementPlan {
+    /// Calculates the retirement plan of a worker
+    pub fn new(birth: YearMonth, category: WorkerCategory) -> RetirementPlan {
+        category.policy().plan(birth)
+    }
+
+    /// The retirement age in fractional years
+    pub fn age(&self) -> f64 {
+        f64::from(self.age_years) + f64::from(self.age_months) / 12.0
+    }
+}
+
+impl fmt::Display for RetirementPlan {
+    /// Formats the plan as "YYYY-MM,age,delay", where the age is in years with
+    /// up to two decimals, e.g. 55, 55.33 or 60.5
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},", self.retirement)?;
+        if self.age_months == 0 {
+            write!(f, "{}", self.age_years)?;
+        } else {
+            let age = format!("{:.2}", self.age());
+            f.write_str(age.trim_end_matches('0'))?;
+        }
+        write!(f, ",{}", self.delay_months)
+    }
+}
+
+/// Parses a birth month and worker category and calculates the retirement plan
+///
+/// # Arguments
+/// * `time` - Birth month in the format "YYYY-MM"
+/// * `tp` - Worker category, a Chinese label or English alias of [`WorkerCategory`]
+pub fn plan_retirement(time: &str, tp: &str) -> Result<RetirementPlan, RetirementError> {
+    Ok(RetirementPlan::new(time.parse()?, tp.parse()?))
+}
+
+/// Calculates the retirement month, age and delay of a worker
+///
+/// # Arguments
+/// * `time` - Birth month in the format "YYYY-MM", as any string type
+/// * `tp` - Worker category, a Chinese label or English alias of [`WorkerCategory`]
+///
+/// # Returns
+/// "YYYY-MM,age,delay", see [`RetirementPlan`]'s `Display`
+///
+/// # Panics
+/// Panics if the birth month is malformed or the category is unknown; use
+/// [`plan_retirement`] to handle the error
+pub fn retire_time(time: impl AsRef<str>, tp: impl AsRef<str>) -> String {
+    plan_retirement(time.as_ref(), tp.as_ref())
+        .unwrap_or_else(|e| panic!("{}", e))
+        .to_string()
 }
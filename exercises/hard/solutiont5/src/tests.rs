@@ -1,10 +1,16 @@
 // src/tests.rs
 mod retirement;
+#[path = "../../common/time_budget.rs"]
+mod time_budget;
 
 #[cfg(test)]
 mod tests {
-    use super::retirement::retire_time;
+    use super::retirement::{
+        plan_retirement, retire_time, RetirementError, RetirementPlan, RetirementPolicy,
+        WorkerCategory, YearMonth, POLICIES,
+    };
     use std::time::{Instant, Duration};
+    use super::time_budget::time_budget;
 
     // 定义测试用例和预期结果
     const TEST_CASES: &[(&str, &str, &str)] = &[
@@ -30,11 +36,87 @@ mod tests {
             let duration = start.elapsed();
 
             // 时间超0.2s，判定不合格
-            if duration <= Duration::from_millis(200) && result == *expected {
+            if duration <= time_budget(Duration::from_millis(200)) && result == *expected {
                 total_score += 10.0;
             }
         }
         println!("Total score: {:.2}", total_score);
         assert_eq!(100.00, total_score);
     }
+
+    // 政策表：改革前后边界、封顶以及自定义政策
+    #[test]
+    fn test_policy_table() {
+        assert_eq!(retire_time("1964-12", "男职工"), "2024-12,60,0");
+        assert_eq!(retire_time("1965-04", "男职工"), "2025-05,60.08,1");
+        assert_eq!(retire_time("1975-01", "原法定退休年龄50周岁女职工"), "2025-02,50.08,1");
+        assert_eq!(retire_time("1975-03", "原法定退休年龄50周岁女职工"), "2025-05,50.17,2");
+        assert_eq!(retire_time("1966-09", "男职工"), "2027-03,60.5,6");
+
+        let ym = |year, month| YearMonth::new(year, month).unwrap();
+        let men = RetirementPolicy::find("男职工").unwrap();
+        assert_eq!(men.delay_months(ym(1976, 9)), 36);
+        assert_eq!(men.delay_months(ym(1976, 8)), 35);
+        assert_eq!(RetirementPolicy::find("Female50").unwrap().original_age, 50);
+        assert!(RetirementPolicy::find("未知类别").is_none());
+
+        // 调整政策只需要修改表中的数据
+        let custom = RetirementPolicy {
+            start: ym(2030, 1),
+            cohort_months: 3,
+            max_delay_months: 12,
+            ..*men
+        };
+        assert_eq!(custom.plan(ym(1970, 1)).retirement, ym(2030, 2));
+        assert_eq!(custom.plan(ym(1985, 1)).delay_months, 12);
+    }
+
+    #[test]
+    fn test_worker_category_and_plan() {
+        for (input, label) in [
+            ("男职工", "男职工"),
+            ("Male", "男职工"),
+            (" men ", "男职工"),
+            ("原法定退休年龄55周岁女职工", "原法定退休年龄55周岁女职工"),
+            ("FEMALE55", "原法定退休年龄55周岁女职工"),
+            ("female-50", "原法定退休年龄50周岁女职工"),
+        ] {
+            let category: WorkerCategory = input.parse().unwrap();
+            assert_eq!(category.label(), label, "{input:?}");
+            assert_eq!(category.policy(), RetirementPolicy::find(label).unwrap());
+        }
+        assert_eq!(
+            WorkerCategory::ALL.map(WorkerCategory::label).to_vec(),
+            POLICIES.iter().map(|policy| policy.label).collect::<Vec<_>>()
+        );
+        assert_eq!("female55".parse(), Ok(WorkerCategory::Female55));
+        assert_eq!(
+            "女职工".parse::<WorkerCategory>(),
+            Err(RetirementError::UnknownCategory("女职工".to_string()))
+        );
+        assert!(RetirementError::UnknownCategory("x".to_string())
+            .to_string()
+            .contains("原法定退休年龄50周岁女职工"));
+
+        let plan = plan_retirement("1971-04", "female55").unwrap();
+        assert_eq!(
+            plan,
+            RetirementPlan {
+                retirement: YearMonth::new(2026, 8).unwrap(),
+                age_years: 55,
+                age_months: 4,
+                delay_months: 4,
+            }
+        );
+        assert!((plan.age() - 55.0 - 4.0 / 12.0).abs() < 1e-12);
+        assert_eq!(plan.to_string(), "2026-08,55.33,4");
+        assert_eq!(retire_time("1995-12", "male"), "2058-12,63,36");
+
+        assert_eq!(
+            plan_retirement("1971/04", "男职工"),
+            Err(RetirementError::InvalidMonth("1971/04".to_string()))
+        );
+        assert!(matches!(plan_retirement("1971-13", "男职工"), Err(RetirementError::InvalidMonth(_))));
+        assert!(matches!(plan_retirement("1971-04", "other"), Err(RetirementError::UnknownCategory(_))));
+    }
 }
@@ -1,16 +1,9 @@
-// use std::collections::HashSet;
-// pub fn new_count_distinct(input_str: &str) -> usize {
-//     input_str.split(",")
-//         .collection::<HashSet<_>>()
-//         .len
-// }
+use crate::distinct::{count_distinct_str, CountOptions};
 
-use std::collections::HashSet;
-
-pub fn new_count_distinct(input_str: &str) -> usize {
-    input_str
-        .split(',')          // Split by comma (not ",")
-        .filter(|s| !s.is_empty())  // Optional: filter out empty strings
-        .collect::<HashSet<_>>()  // Collect into HashSet (fixed typo in "collect")
-        .len()               // Call len() as a method (added parentheses)
+/// Counts the distinct non-empty tokens of a comma-separated string
+///
+/// Accepts any string type. Tokens are compared as they are; see
+/// [`CountOptions`] for trimming, case folding and other delimiters.
+pub fn new_count_distinct(input_str: impl AsRef<str>) -> usize {
+    count_distinct_str(input_str.as_ref(), &CountOptions::default())
 }
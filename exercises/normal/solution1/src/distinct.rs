@@ -0,0 +1,403 @@
+//! Counting distinct tokens, exactly or approximately
+//!
+//! Tokens can come from a string, any iterator, or a [`BufRead`] stream that
+//! is split as it is read. [`CountOptions`] decides when two tokens count as
+//! the same. For streams too large to remember every token, [`HyperLogLog`]
+//! estimates the count in a fixed amount of memory.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufRead};
+
+use unicode_normalization::UnicodeNormalization;
+
+/// Why a count failed
+#[derive(Debug)]
+pub enum DistinctError {
+    /// Reading failed, or the input is not valid UTF-8
+    Io(io::Error),
+    /// [`HyperLogLog`] cannot reach this relative error
+    ErrorBoundOutOfRange(f64),
+}
+
+impl fmt::Display for DistinctError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DistinctError::Io(e) => write!(f, "failed to read tokens: {}", e),
+            DistinctError::ErrorBoundOutOfRange(error) => write!(
+                f,
+                "error bound out of range {:.4}-1: {}",
+                HyperLogLog::error_for(HyperLogLog::MAX_PRECISION),
+                error
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DistinctError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DistinctError::Io(e) => Some(e),
+            DistinctError::ErrorBoundOutOfRange(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for DistinctError {
+    fn from(e: io::Error) -> Self {
+        DistinctError::Io(e)
+    }
+}
+
+/// A Unicode normalization form, so that differently encoded but equivalent
+/// text counts once
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Normalization {
+    /// Compare the text as it is
+    #[default]
+    None,
+    /// Canonical composition: "e" + combining acute equals "é"
+    Nfc,
+    /// Canonical decomposition
+    Nfd,
+    /// Compatibility composition: also "ﬁ" equals "fi" and "Ａ" equals "A"
+    Nfkc,
+    /// Compatibility decomposition
+    Nfkd,
+}
+
+/// How input is split into tokens and when two tokens are the same
+///
+/// The default splits on `,`, skips empty tokens and compares the rest as
+/// they are.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CountOptions {
+    delimiter: char,
+    trim: bool,
+    case_fold: bool,
+    normalization: Normalization,
+}
+
+impl Default for CountOptions {
+    fn default() -> Self {
+        CountOptions {
+            delimiter: ',',
+            trim: false,
+            case_fold: false,
+            normalization: Normalization::None,
+        }
+    }
+}
+
+impl CountOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Splits the input on `delimiter` instead of `,`
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Strips whitespace around each token, so that "a" and " a" are the same
+    pub fn trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Ignores case with full Unicode case folding, so that "A" and "a" are
+    /// the same, and so are "ß", "SS" and "ss"
+    pub fn case_fold(mut self, case_fold: bool) -> Self {
+        self.case_fold = case_fold;
+        self
+    }
+
+    /// Normalizes each token to a Unicode normalization form
+    pub fn normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    /// Returns the form of `token` that is compared, or `None` if it is empty
+    /// and should be skipped
+    ///
+    /// Case folding can undo a normalization form, so a folded token is
+    /// normalized again afterwards.
+    pub fn normalize(&self, token: &str) -> Option<String> {
+        let token = if self.trim { token.trim() } else { token };
+        if token.is_empty() {
+            return None;
+        }
+        let token = self.normalization.apply(token);
+        Some(if self.case_fold {
+            self.normalization
+                .apply(&caseless::default_case_fold_str(&token))
+        } else {
+            token
+        })
+    }
+}
+
+impl Normalization {
+    fn apply(self, token: &str) -> String {
+        match self {
+            Normalization::None => token.to_string(),
+            Normalization::Nfc => token.nfc().collect(),
+            Normalization::Nfd => token.nfd().collect(),
+            Normalization::Nfkc => token.nfkc().collect(),
+            Normalization::Nfkd => token.nfkd().collect(),
+        }
+    }
+}
+
+/// Counts the distinct tokens in `input`, split on the configured delimiter
+pub fn count_distinct_str(input: &str, options: &CountOptions) -> usize {
+    count_distinct(input.split(options.delimiter), options)
+}
+
+/// Counts the distinct tokens of an iterator
+///
+/// The items are taken as tokens already; the delimiter is not used.
+pub fn count_distinct<I>(tokens: I, options: &CountOptions) -> usize
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    tokens
+        .into_iter()
+        .filter_map(|token| options.normalize(token.as_ref()))
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// Counts the distinct tokens of a stream, split on the configured delimiter
+/// as it is read
+///
+/// Only the distinct tokens are kept in memory, not the whole input.
+pub fn count_distinct_reader<R: BufRead>(
+    reader: R,
+    options: &CountOptions,
+) -> Result<usize, DistinctError> {
+    let mut seen = HashSet::new();
+    for token in tokens(reader, options.delimiter) {
+        if let Some(token) = options.normalize(&token?) {
+            seen.insert(token);
+        }
+    }
+    Ok(seen.len())
+}
+
+/// Estimates the distinct tokens of a stream with a [`HyperLogLog`] sketch
+///
+/// # Arguments
+///
+/// * `error` - The relative standard error to aim for, see
+///   [`HyperLogLog::with_error`]
+pub fn estimate_distinct_reader<R: BufRead>(
+    reader: R,
+    options: &CountOptions,
+    error: f64,
+) -> Result<u64, DistinctError> {
+    let mut sketch = HyperLogLog::with_error(error)?;
+    for token in tokens(reader, options.delimiter) {
+        if let Some(token) = options.normalize(&token?) {
+            sketch.insert(&token);
+        }
+    }
+    Ok(sketch.estimate())
+}
+
+/// Splits a stream on `delimiter`, reading one token at a time
+///
+/// A token that is not valid UTF-8 is an [`io::ErrorKind::InvalidData`] error.
+pub fn tokens<R: BufRead>(reader: R, delimiter: char) -> Tokens<R> {
+    let mut encoded = [0; 4];
+    Tokens {
+        reader,
+        delimiter: delimiter.encode_utf8(&mut encoded).as_bytes().to_vec(),
+        done: false,
+    }
+}
+
+/// Iterator returned by [`tokens`]
+#[derive(Debug)]
+pub struct Tokens<R> {
+    reader: R,
+    /// The delimiter as UTF-8
+    delimiter: Vec<u8>,
+    done: bool,
+}
+
+impl<R: BufRead> Iterator for Tokens<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<io::Result<String>> {
+        if self.done {
+            return None;
+        }
+        let last = *self.delimiter.last().expect("a char is at least one byte");
+        let mut token = Vec::new();
+        loop {
+            match self.reader.read_until(last, &mut token) {
+                Ok(0) => {
+                    self.done = true;
+                    if token.is_empty() {
+                        return None;
+                    }
+                    break;
+                }
+                Ok(_) if token.ends_with(&self.delimiter) => {
+                    token.truncate(token.len() - self.delimiter.len());
+                    break;
+                }
+                // The last byte of a multi-byte delimiter alone; keep reading
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        Some(String::from_utf8(token).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)))
+    }
+}
+
+/// HyperLogLog sketch for estimating how many distinct items it has seen
+///
+/// Uses `2^precision` one-byte registers whatever the number of items; the
+/// relative standard error of the estimate is about `1.04 / sqrt(2^precision)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperLogLog {
+    precision: u8,
+    /// The most leading zeros (plus one) seen among the hashes sent to each
+    /// register
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    pub const MIN_PRECISION: u8 = 4;
+    pub const MAX_PRECISION: u8 = 18;
+
+    /// Creates a sketch with `2^precision` registers
+    ///
+    /// # Panics
+    ///
+    /// Panics if `precision` is outside
+    /// [`MIN_PRECISION`](Self::MIN_PRECISION)..=[`MAX_PRECISION`](Self::MAX_PRECISION).
+    pub fn new(precision: u8) -> Self {
+        assert!(
+            (Self::MIN_PRECISION..=Self::MAX_PRECISION).contains(&precision),
+            "precision out of range: {}",
+            precision
+        );
+        HyperLogLog {
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+
+    /// Creates the smallest sketch whose relative standard error is at most
+    /// `error`
+    ///
+    /// Bounds below about 0.2% need more than
+    /// [`MAX_PRECISION`](Self::MAX_PRECISION) and are rejected.
+    pub fn with_error(error: f64) -> Result<Self, DistinctError> {
+        if !(error > 0.0 && error < 1.0) {
+            return Err(DistinctError::ErrorBoundOutOfRange(error));
+        }
+        let registers = (1.04 / error).powi(2);
+        let precision = registers.log2().ceil().max(f64::from(Self::MIN_PRECISION));
+        if precision > f64::from(Self::MAX_PRECISION) {
+            return Err(DistinctError::ErrorBoundOutOfRange(error));
+        }
+        Ok(Self::new(precision as u8))
+    }
+
+    /// The relative standard error with `2^precision` registers
+    fn error_for(precision: u8) -> f64 {
+        1.04 / f64::from(1u32 << precision).sqrt()
+    }
+
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// The relative standard error of [`estimate`](Self::estimate)
+    pub fn relative_error(&self) -> f64 {
+        Self::error_for(self.precision)
+    }
+
+    /// Records an item; recording it again changes nothing
+    ///
+    /// Items are hashed with [`Fnv1a`], which has no random keys, so the same
+    /// items always give the same estimate.
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) {
+        let mut hasher = Fnv1a::default();
+        item.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let index = (hash >> (64 - self.precision)) as usize;
+        let rest = hash << self.precision;
+        let rank = (rest.leading_zeros() + 1).min(64 - u32::from(self.precision) + 1) as u8;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    /// Estimates how many distinct items have been inserted
+    pub fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|&r| 2f64.powi(-i32::from(r)))
+            .sum();
+        let raw = alpha * m * m / sum;
+
+        // Few items leave registers empty; linear counting is better there
+        let empty = self.registers.iter().filter(|&&r| r == 0).count();
+        let estimate = if raw <= 2.5 * m && empty > 0 {
+            m * (m / empty as f64).ln()
+        } else {
+            raw
+        };
+        estimate.round() as u64
+    }
+}
+
+/// The 64-bit FNV-1a hash, finished with the MurmurHash3 mixer
+///
+/// FNV-1a alone leaves the high bits poorly mixed for short inputs, and
+/// [`HyperLogLog`] relies on exactly those bits.
+#[derive(Debug, Clone)]
+pub struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        let mut h = self.0;
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+        h ^ (h >> 33)
+    }
+}
@@ -0,0 +1,4 @@
+//! Counting distinct tokens
+
+pub mod count_distinct;
+pub mod distinct;
@@ -1,4 +1,4 @@
-mod count_distinct;
+use solution1::count_distinct;
 
 fn main() {
     let input_str: &str = "a,b,cd,b,e,e,d,a";
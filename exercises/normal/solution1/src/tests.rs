@@ -1,9 +1,15 @@
 // src/tests.rs
 mod count_distinct;
+mod distinct;
 
 #[cfg(test)]
 mod tests {
     use super::count_distinct::new_count_distinct;
+    use super::distinct::{
+        count_distinct, count_distinct_reader, count_distinct_str, estimate_distinct_reader,
+        tokens, CountOptions, DistinctError, HyperLogLog, Normalization,
+    };
+    use std::io::{BufReader, Cursor};
     // 定义测试用例和预期结果
     const TEST_CASES: &[(&str, usize)] = &[
         ("a,b,c,a,e,cd", 5),
@@ -25,4 +31,102 @@ mod tests {
         println!("Total score: {:.2}", total_score);
         assert_eq!(100.00, total_score);
     }
+
+    #[test]
+    fn test_count_options() {
+        let default = CountOptions::new();
+        assert_eq!(count_distinct_str("a, a ,b,,", &default), 3);
+        let trimmed = CountOptions::new().trim(true);
+        assert_eq!(count_distinct_str("a, a ,b, ,", &trimmed), 2);
+
+        let folded = CountOptions::new().case_fold(true);
+        assert_eq!(count_distinct_str("A,a,Ä,ä,ß", &folded), 3);
+        // 完整的大小写折叠：ß 与 SS、ss 相同，ﬃ 与 FFI 相同
+        assert_eq!(count_distinct_str("ß,SS,ss,ẞ,\u{fb03},FFI", &folded), 2);
+        assert_eq!(count_distinct_str("ß,SS", &CountOptions::new()), 2);
+
+        // 组合字符与兼容字符
+        let composed = "\u{e9},e\u{301}";
+        assert_eq!(count_distinct_str(composed, &default), 2);
+        let nfc = CountOptions::new().normalization(Normalization::Nfc);
+        assert_eq!(count_distinct_str(composed, &nfc), 1);
+        let nfd = CountOptions::new().normalization(Normalization::Nfd);
+        assert_eq!(count_distinct_str(composed, &nfd), 1);
+        assert_eq!(count_distinct_str("\u{fb01},fi,Ａ,A", &nfc), 4);
+        let nfkc = CountOptions::new().normalization(Normalization::Nfkc);
+        assert_eq!(count_distinct_str("\u{fb01},fi,Ａ,A", &nfkc), 2);
+        let nfkd = nfkc.clone().normalization(Normalization::Nfkd).case_fold(true);
+        assert_eq!(count_distinct_str("\u{fb01},FI,Ａ,a", &nfkd), 2);
+
+        assert_eq!(count_distinct_str("a|b|a", &CountOptions::new().delimiter('|')), 2);
+        assert_eq!(count_distinct_str("好，好，爱", &CountOptions::new().delimiter('，')), 2);
+        assert_eq!(count_distinct(["x", "X", " x"], &folded.clone().trim(true)), 1);
+        assert_eq!(count_distinct(vec![String::from("x"), String::new()], &default), 1);
+    }
+
+    #[test]
+    fn test_count_streams() {
+        let lines = CountOptions::new().delimiter('\n').trim(true).case_fold(true);
+        let input = "apple\nBanana\r\napple\n\nbanana\ncherry";
+        assert_eq!(count_distinct_reader(Cursor::new(input), &lines).unwrap(), 3);
+
+        // 多字节分隔符跨越缓冲区边界
+        let input = "甲，乙，甲，丙，乙";
+        let reader = BufReader::with_capacity(1, input.as_bytes());
+        let wide = CountOptions::new().delimiter('，');
+        assert_eq!(count_distinct_reader(reader, &wide).unwrap(), 3);
+        let split: Vec<String> = tokens(Cursor::new("a，b，"), '，').map(Result::unwrap).collect();
+        assert_eq!(split, vec!["a", "b"]);
+        // "丌" 的 UTF-8 编码以 0x8C 结尾，与 "，" 的末字节相同，不能在这里切分
+        let split: Vec<String> =
+            tokens(Cursor::new("丌，丌丌"), '，').map(Result::unwrap).collect();
+        assert_eq!(split, vec!["丌", "丌丌"]);
+
+        let invalid = Cursor::new(b"a,\xff,b".to_vec());
+        match count_distinct_reader(invalid, &CountOptions::new()) {
+            Err(DistinctError::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidData),
+            other => panic!("expected an I/O error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_hyperloglog() {
+        let mut sketch = HyperLogLog::with_error(0.01).unwrap();
+        assert_eq!(sketch.precision(), 14);
+        assert!(sketch.relative_error() <= 0.01);
+        assert_eq!(sketch.estimate(), 0);
+
+        for round in 0..3 {
+            for n in 0..10 {
+                sketch.insert(&n);
+            }
+            // 重复插入不影响估计；数量很少时用线性计数，几乎精确
+            assert_eq!(sketch.estimate(), 10, "round {round}");
+        }
+
+        let mut sketch = HyperLogLog::with_error(0.01).unwrap();
+        for n in 0..100_000u32 {
+            sketch.insert(&n);
+            sketch.insert(&(n / 2));
+        }
+        let estimate = sketch.estimate() as f64;
+        // 三倍标准误差以内
+        assert!((estimate - 100_000.0).abs() / 100_000.0 < 3.0 * sketch.relative_error());
+
+        let small = HyperLogLog::with_error(0.5).unwrap();
+        assert_eq!(small.precision(), HyperLogLog::MIN_PRECISION);
+        for error in [0.0, 1.0, -0.1, f64::NAN, 0.001] {
+            assert!(matches!(
+                HyperLogLog::with_error(error),
+                Err(DistinctError::ErrorBoundOutOfRange(_))
+            ));
+        }
+
+        let input: String = (0..20_000).map(|n| format!(" Item{} ,item{},", n, n % 5_000)).collect();
+        let options = CountOptions::new().trim(true).case_fold(true);
+        let exact = count_distinct_reader(Cursor::new(&input), &options).unwrap();
+        assert_eq!(exact, 20_000);
+        let estimate = estimate_distinct_reader(Cursor::new(&input), &options, 0.02).unwrap();
+        assert!((estimate as f64 - 20_000.0).abs() < 20_000.0 * 0.06, "{estimate}");
+    }
 }
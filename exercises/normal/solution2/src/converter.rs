@@ -1,64 +1,383 @@
-pub fn convert_base(num_str: &str, to_base: u32) -> String {
-    let (value, base_from) = match parse_input(num_str) {
-        Ok((v, b)) => (v, b),
-        Err(_) => return String::new(),
-    };
+//! Conversion of numbers between bases 2 to 36
+//!
+//! Values are kept as digit lists rather than machine integers, so there is
+//! no upper bound on their size. Digits above 9 are the letters `a` to `z`;
+//! input accepts either case.
+//!
+//! [`parse_literal`] reads "value(base)", `0x`/`0o`/`0b` prefixes and
+//! subscript bases such as "1010₂"; [`FormatOptions`] controls how results
+//! are written.
+
+use std::fmt;
+
+/// The smallest supported base
+pub const MIN_BASE: u32 = 2;
+
+/// The largest supported base, using the digits 0-9 and a-z
+pub const MAX_BASE: u32 = 36;
+
+/// How many fractional digits [`convert`] produces before cutting off a
+/// repeating expansion
+pub const DEFAULT_PRECISION: usize = 20;
+
+/// Why a conversion failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConversionError {
+    /// The input is not in any of the literal forms [`parse_literal`] reads
+    InvalidFormat(String),
+    /// The base is outside 2-36
+    BaseOutOfRange(u32),
+    /// The value has no digits
+    EmptyValue,
+    /// A character is not a digit of the base
+    InvalidDigit { digit: char, base: u32 },
+}
 
-    if !(2..=16).contains(&base_from) || !(2..=16).contains(&to_base) {
-        return String::new();
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::InvalidFormat(s) => {
+                write!(f, "not a number literal: {:?}", s)
+            }
+            ConversionError::BaseOutOfRange(base) => {
+                write!(f, "base out of range {}-{}: {}", MIN_BASE, MAX_BASE, base)
+            }
+            ConversionError::EmptyValue => write!(f, "the value has no digits"),
+            ConversionError::InvalidDigit { digit, base } => {
+                write!(f, "{:?} is not a digit in base {}", digit, base)
+            }
+        }
     }
+}
 
+impl std::error::Error for ConversionError {}
 
-    let decimal = match to_decimal(value, base_from) {
-        Ok(d) => d,
-        Err(_) => return String::new(),
-    };
+/// A signed number with a fractional part, as digits in some base
+///
+/// The integer digits have no leading zeros and the fraction digits have no
+/// trailing zeros, so equal values in the same base compare equal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Numeral {
+    negative: bool,
+    base: u32,
+    /// Most significant first; empty for zero
+    integer: Vec<u32>,
+    /// Most significant first
+    fraction: Vec<u32>,
+}
+
+impl Numeral {
+    /// Parses a value such as "-1a.8" in the given base
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - An optional sign, digits, and optionally a point followed by
+    ///   more digits
+    /// * `base` - The base of the digits, 2-36
+    pub fn parse(value: &str, base: u32) -> Result<Numeral, ConversionError> {
+        check_base(base)?;
+        let (negative, unsigned) = match value.as_bytes().first() {
+            Some(b'-') => (true, &value[1..]),
+            Some(b'+') => (false, &value[1..]),
+            _ => (false, value),
+        };
+        let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(ConversionError::EmptyValue);
+        }
+
+        let digits = |s: &str| {
+            s.chars()
+                .map(|c| {
+                    c.to_digit(base)
+                        .ok_or(ConversionError::InvalidDigit { digit: c, base })
+                })
+                .collect::<Result<Vec<u32>, _>>()
+        };
+        Ok(Numeral::new(
+            negative,
+            base,
+            digits(integer)?,
+            digits(fraction)?,
+        ))
+    }
+
+    /// Returns the same value in another base
+    ///
+    /// The integer part is always exact. The fractional part stops after
+    /// `precision` digits if it does not terminate sooner; the remaining
+    /// digits are truncated, not rounded.
+    pub fn to_base(&self, base: u32, precision: usize) -> Result<Numeral, ConversionError> {
+        check_base(base)?;
 
+        // Horner's scheme carried out on the digits of the new base
+        let mut integer: Vec<u32> = Vec::new(); // least significant first
+        for &digit in &self.integer {
+            let mut carry = digit;
+            for d in integer.iter_mut() {
+                let value = *d * self.base + carry;
+                *d = value % base;
+                carry = value / base;
+            }
+            while carry > 0 {
+                integer.push(carry % base);
+                carry /= base;
+            }
+        }
+        integer.reverse();
 
-    from_decimal(decimal, to_base)
+        // Multiplying the fraction by the new base shifts one digit of it
+        // out past the point
+        let mut rest = self.fraction.clone();
+        let mut fraction = Vec::new();
+        while !rest.is_empty() && fraction.len() < precision {
+            let mut carry = 0;
+            for d in rest.iter_mut().rev() {
+                let value = *d * base + carry;
+                *d = value % self.base;
+                carry = value / self.base;
+            }
+            fraction.push(carry);
+            trim_end_zeros(&mut rest);
+        }
 
+        Ok(Numeral::new(self.negative, base, integer, fraction))
+    }
+
+    /// The base the digits are written in
+    pub fn base(&self) -> u32 {
+        self.base
+    }
+
+    /// Writes the number with the given options
+    pub fn format(&self, options: &FormatOptions) -> String {
+        let digit = |&d: &u32| {
+            let c = char::from_digit(d, self.base).expect("digits are below the base");
+            if options.uppercase {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        };
+        let mut integer: Vec<char> = self.integer.iter().map(digit).collect();
+        let padding = options.width.max(1).saturating_sub(integer.len());
+        integer.splice(0..0, std::iter::repeat_n('0', padding));
+
+        let mut s = String::new();
+        if self.negative {
+            s.push('-');
+        }
+        if options.prefix {
+            s.push_str(prefix_for(self.base).unwrap_or(""));
+        }
+        match options.group {
+            Some((size, separator)) if size > 0 => {
+                for (i, c) in integer.iter().enumerate() {
+                    if i > 0 && (integer.len() - i).is_multiple_of(size) {
+                        s.push(separator);
+                    }
+                    s.push(*c);
+                }
+            }
+            _ => s.extend(&integer),
+        }
+        if !self.fraction.is_empty() {
+            s.push('.');
+            s.extend(self.fraction.iter().map(digit));
+        }
+        s
+    }
+
+    fn new(negative: bool, base: u32, mut integer: Vec<u32>, mut fraction: Vec<u32>) -> Numeral {
+        let leading_zeros = integer.iter().take_while(|&&d| d == 0).count();
+        integer.drain(..leading_zeros);
+        trim_end_zeros(&mut fraction);
+        Numeral {
+            negative: negative && !(integer.is_empty() && fraction.is_empty()),
+            base,
+            integer,
+            fraction,
+        }
+    }
 }
-fn parse_input(s: &str) -> Result<(&str, u32), &str> {
-    let parts: Vec<&str> = s.split(')').collect();
-    if parts.len() != 2 {
-        return Err("Invalid format");
+
+impl fmt::Display for Numeral {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format(&FormatOptions::default()))
     }
-    let (value_part, base_part) = parts[0].split_once('(').ok_or("Invalid format")?;
-    let base = base_part.parse().map_err(|_| "Invalid base")?;
-    
-    Ok((value_part, base))
 }
 
+/// How [`Numeral::format`] writes a number
+///
+/// The default is lowercase digits with no prefix, padding or grouping,
+/// which is what [`Numeral`]'s `Display` produces.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FormatOptions {
+    uppercase: bool,
+    prefix: bool,
+    width: usize,
+    group: Option<(usize, char)>,
+}
 
-fn to_decimal(s: &str, base: u32) -> Result<u64, &str> {
-    let mut result = 0;
-    for c in s.chars() {
-        let digit = c.to_uppercase()
-            .next()
-            .and_then(|c| c.to_digit(base))
-            .ok_or("Invalid digit")? as u64;
-        
-        result = result * base as u64 + digit;
+impl FormatOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes the digits above 9 as `A` to `Z`
+    pub fn uppercase(mut self, uppercase: bool) -> Self {
+        self.uppercase = uppercase;
+        self
+    }
+
+    /// Starts bases 2, 8 and 16 with `0b`, `0o` and `0x`; other bases have
+    /// no prefix
+    pub fn prefix(mut self, prefix: bool) -> Self {
+        self.prefix = prefix;
+        self
     }
-    Ok(result)
-}
 
+    /// Pads the integer part with leading zeros to at least `width` digits
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
 
-fn from_decimal(mut n: u64, base: u32) -> String {
-    if n == 0 {
-        return "0".to_string();
+    /// Separates the integer digits into groups of `size`, counted from the
+    /// point; a size of 0 turns grouping off
+    pub fn group(mut self, size: usize, separator: char) -> Self {
+        self.group = Some((size, separator));
+        self
     }
+}
+
+/// Converts `value` from one base to another, with up to
+/// [`DEFAULT_PRECISION`] fractional digits
+pub fn convert(value: &str, from_base: u32, to_base: u32) -> Result<String, ConversionError> {
+    convert_with_precision(value, from_base, to_base, DEFAULT_PRECISION)
+}
+
+/// Converts `value` from one base to another, with up to `precision`
+/// fractional digits
+pub fn convert_with_precision(
+    value: &str,
+    from_base: u32,
+    to_base: u32,
+    precision: usize,
+) -> Result<String, ConversionError> {
+    let numeral = Numeral::parse(value, from_base)?;
+    Ok(numeral.to_base(to_base, precision)?.to_string())
+}
+
+/// Converts a number literal into `to_base`
+///
+/// See [`parse_literal`] for the accepted forms.
+pub fn try_convert_base(num_str: &str, to_base: u32) -> Result<String, ConversionError> {
+    try_convert_base_with(num_str, to_base, &FormatOptions::default())
+}
+
+/// Converts a number literal into `to_base` and writes it with `options`
+pub fn try_convert_base_with(
+    num_str: &str,
+    to_base: u32,
+    options: &FormatOptions,
+) -> Result<String, ConversionError> {
+    let numeral = parse_literal(num_str)?.to_base(to_base, DEFAULT_PRECISION)?;
+    Ok(numeral.format(options))
+}
+
+/// Converts a number literal such as "value(base)" into `to_base`
+///
+/// Accepts any string type. Returns an empty string if the input is invalid;
+/// use [`try_convert_base`] to find out why.
+pub fn convert_base(num_str: impl AsRef<str>, to_base: u32) -> String {
+    try_convert_base(num_str.as_ref(), to_base).unwrap_or_default()
+}
+
+/// Parses a number literal in any of these forms:
+///
+/// - "value(base)", e.g. "ff(16)"
+/// - a `0x`, `0o` or `0b` prefix after the sign, e.g. "-0x1f"
+/// - a subscript base, e.g. "1010₂"
+/// - plain decimal, e.g. "12.5"
+///
+/// Surrounding whitespace is ignored, and `_` may separate digits.
+pub fn parse_literal(s: &str) -> Result<Numeral, ConversionError> {
+    let invalid = || ConversionError::InvalidFormat(s.to_string());
+    let literal = s.trim();
 
-    let mut digits = Vec::new();
-    while n > 0 {
-        let rem = (n % base as u64) as u8;
-        digits.push(match rem {
-            0..=9 => (b'0' + rem as u8) as char,
-            10..=15 => (b'a' + rem - 10) as char, 
-            _ => unreachable!(),
+    let (value, base) = if literal.contains(['(', ')']) {
+        let (value, base) = literal
+            .strip_suffix(')')
+            .and_then(|rest| rest.split_once('('))
+            .ok_or_else(invalid)?;
+        (
+            value.trim_end().to_string(),
+            base.trim().parse().map_err(|_| invalid())?,
+        )
+    } else if literal.ends_with(is_subscript_digit) {
+        let value = literal.trim_end_matches(is_subscript_digit);
+        let base: String = literal[value.len()..]
+            .chars()
+            .map(|c| char::from_digit(c as u32 - '₀' as u32, 10).unwrap())
+            .collect();
+        (value.to_string(), base.parse().map_err(|_| invalid())?)
+    } else {
+        let sign_len = usize::from(literal.starts_with(['-', '+']));
+        let (sign, unsigned) = literal.split_at(sign_len);
+        let prefixed = unsigned.get(..2).and_then(|prefix| {
+            [2, 8, 16]
+                .into_iter()
+                .find(|&base| prefix_for(base).is_some_and(|p| p.eq_ignore_ascii_case(prefix)))
         });
-        n /= base as u64;
+        match prefixed {
+            Some(base) => (format!("{sign}{}", &unsigned[2..]), base),
+            None => (literal.to_string(), 10),
+        }
+    };
+
+    Numeral::parse(&remove_separators(&value).ok_or_else(invalid)?, base)
+}
+
+/// The literal prefix for a base, if it has one
+fn prefix_for(base: u32) -> Option<&'static str> {
+    match base {
+        2 => Some("0b"),
+        8 => Some("0o"),
+        16 => Some("0x"),
+        _ => None,
+    }
+}
+
+fn is_subscript_digit(c: char) -> bool {
+    ('₀'..='₉').contains(&c)
+}
+
+/// Drops the `_` separators, each of which must sit between two digits
+fn remove_separators(value: &str) -> Option<String> {
+    let chars: Vec<char> = value.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        let is_digit = |j: Option<usize>| {
+            j.and_then(|j| chars.get(j))
+                .is_some_and(|c| c.is_ascii_alphanumeric())
+        };
+        if c == '_' && !(is_digit(i.checked_sub(1)) && is_digit(Some(i + 1))) {
+            return None;
+        }
     }
+    Some(chars.into_iter().filter(|&c| c != '_').collect())
+}
 
-    digits.iter().rev().collect()
+fn check_base(base: u32) -> Result<(), ConversionError> {
+    if (MIN_BASE..=MAX_BASE).contains(&base) {
+        Ok(())
+    } else {
+        Err(ConversionError::BaseOutOfRange(base))
+    }
+}
+
+fn trim_end_zeros(digits: &mut Vec<u32>) {
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
 }
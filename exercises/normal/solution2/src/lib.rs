@@ -0,0 +1,3 @@
+//! Converting numbers between bases
+
+pub mod converter;
@@ -1,4 +1,4 @@
-mod converter;
+use solution2::converter;
 
 fn main() {
     let num_str = "9(10)";
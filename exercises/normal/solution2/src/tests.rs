@@ -3,7 +3,10 @@
 mod converter;
 #[cfg(test)]
 mod tests {
-    use super::converter::convert_base;
+    use super::converter::{
+        convert, convert_base, convert_with_precision, parse_literal, try_convert_base,
+        try_convert_base_with, ConversionError, FormatOptions,
+    };
 
     // 定义测试用例和预期结果
     const TEST_CASES: &[(&str, u32, &str)] = &[
@@ -29,4 +32,98 @@ mod tests {
         println!("Total score: {:.2}", total_score);
         assert_eq!(100.00, total_score);
     }
+
+    #[test]
+    fn test_convert_errors_and_extensions() {
+        // 36 进制与大小写
+        assert_eq!(convert("zz", 36, 10), Ok("1295".to_string()));
+        assert_eq!(convert("ZZ", 36, 2), Ok("10100001111".to_string()));
+        assert_eq!(convert("1295", 10, 36), Ok("zz".to_string()));
+
+        // 负数与前导零
+        assert_eq!(convert("-255", 10, 16), Ok("-ff".to_string()));
+        assert_eq!(convert("+0010", 2, 10), Ok("2".to_string()));
+        assert_eq!(convert("-0", 10, 2), Ok("0".to_string()));
+
+        // 小数：有限小数精确转换，循环小数按精度截断
+        assert_eq!(convert("0.1", 2, 10), Ok("0.5".to_string()));
+        assert_eq!(convert("-10.101", 2, 10), Ok("-2.625".to_string()));
+        assert_eq!(convert(".8", 16, 10), Ok("0.5".to_string()));
+        assert_eq!(convert_with_precision("0.1", 10, 2, 8), Ok("0.00011001".to_string()));
+        assert_eq!(convert_with_precision("0.1", 3, 10, 5), Ok("0.33333".to_string()));
+        assert_eq!(convert_with_precision("1.5", 10, 2, 0), Ok("1".to_string()));
+
+        // 超过 u64 与 u128 的整数
+        let big = "1".repeat(200);
+        assert_eq!(convert(&big, 2, 16), Ok(format!("{}{}", "ff", "f".repeat(48))));
+        assert_eq!(
+            convert("340282366920938463463374607431768211456", 10, 16),
+            Ok(format!("1{}", "0".repeat(32)))
+        );
+        assert_eq!(
+            convert(&u128::MAX.to_string(), 10, 36),
+            Ok("f5lxx1zz5pnorynqglhzmsp33".to_string())
+        );
+
+        assert_eq!(convert("12", 10, 37), Err(ConversionError::BaseOutOfRange(37)));
+        assert_eq!(convert("12", 1, 10), Err(ConversionError::BaseOutOfRange(1)));
+        assert_eq!(convert("-", 10, 2), Err(ConversionError::EmptyValue));
+        assert_eq!(convert("129", 8, 2), Err(ConversionError::InvalidDigit { digit: '9', base: 8 }));
+        assert_eq!(
+            try_convert_base("12(10", 2),
+            Err(ConversionError::InvalidFormat("12(10".to_string()))
+        );
+        assert_eq!(try_convert_base("ff(16)", 10), Ok("255".to_string()));
+        assert_eq!(convert_base("12(x)", 2), "");
+    }
+
+    #[test]
+    fn test_literals_and_formatting() {
+        for (literal, expected) in [
+            ("ff(16)", "255"),
+            ("  ff ( 16 )  ", "255"),
+            ("0x1F", "31"),
+            ("-0o17", "-15"),
+            ("+0B1010", "10"),
+            ("1010₂", "10"),
+            ("zz₃₆", "1295"),
+            ("1_000_000", "1000000"),
+            ("0xdead_beef", "3735928559"),
+            ("1_0.0_1(2)", "2.25"),
+            ("\t42\n", "42"),
+        ] {
+            assert_eq!(try_convert_base(literal, 10), Ok(expected.to_string()), "{literal:?}");
+        }
+        assert_eq!(parse_literal("0b11").unwrap().base(), 2);
+        assert_eq!(parse_literal("11₈").unwrap().base(), 8);
+        assert_eq!(parse_literal("11").unwrap().base(), 10);
+
+        for literal in ["", "_1", "1_", "1__0", "0x_ff", "1_.5", "12₀", "ff(16", "(16)ff"] {
+            assert!(parse_literal(literal).is_err(), "{literal:?}");
+        }
+        assert_eq!(
+            parse_literal("0b12"),
+            Err(ConversionError::InvalidDigit { digit: '2', base: 2 })
+        );
+
+        let options = FormatOptions::new().uppercase(true).prefix(true);
+        assert_eq!(try_convert_base_with("255", 16, &options), Ok("0xFF".to_string()));
+        assert_eq!(try_convert_base_with("-5", 2, &options), Ok("-0b101".to_string()));
+        assert_eq!(try_convert_base_with("35", 36, &options), Ok("Z".to_string()));
+
+        let options = FormatOptions::new().width(8).group(4, '_');
+        assert_eq!(try_convert_base_with("5", 2, &options), Ok("0000_0101".to_string()));
+        assert_eq!(try_convert_base_with("0", 2, &options), Ok("0000_0000".to_string()));
+        let options = FormatOptions::new().group(3, ',');
+        assert_eq!(try_convert_base_with("0x12d687", 10, &options), Ok("1,234,567".to_string()));
+        assert_eq!(try_convert_base_with("1234.5", 10, &options), Ok("1,234.5".to_string()));
+        let options = FormatOptions::new().group(0, ',').width(0);
+        assert_eq!(try_convert_base_with("0", 10, &options), Ok("0".to_string()));
+
+        // 带前缀和分组的输出可以原样解析回来
+        let options = FormatOptions::new().prefix(true).group(4, '_');
+        let formatted = try_convert_base_with("3735928559", 16, &options).unwrap();
+        assert_eq!(formatted, "0xdead_beef");
+        assert_eq!(try_convert_base(&formatted, 10), Ok("3735928559".to_string()));
+    }
 }
@@ -0,0 +1,278 @@
+//! The generalized birthday problem: among `n` people, how likely is it that
+//! at least `k` of them share a birthday?
+//!
+//! Years may have any number of days, and birthdays need not be spread
+//! evenly over them. Every probability is returned at full `f64` precision.
+
+use std::fmt;
+
+/// Why a birthday distribution was rejected
+#[derive(Debug, Clone, PartialEq)]
+pub enum BirthdayError {
+    /// The distribution has no days
+    NoDays,
+    /// A day's weight is negative, infinite or NaN
+    InvalidWeight { day: usize, weight: f64 },
+    /// Every weight is zero, so nobody can be born on any day
+    ZeroTotalWeight,
+    /// A target probability is not within `0.0..=1.0`
+    InvalidTarget(f64),
+    /// The answer is above `limit` people, more than
+    /// [`BirthdayProblem::min_people`] will search
+    SearchTooLarge { limit: u32 },
+}
+
+impl fmt::Display for BirthdayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BirthdayError::NoDays => write!(f, "the year has no days"),
+            BirthdayError::InvalidWeight { day, weight } => {
+                write!(f, "invalid weight for day {}: {}", day, weight)
+            }
+            BirthdayError::ZeroTotalWeight => write!(f, "the weights add up to zero"),
+            BirthdayError::InvalidTarget(target) => {
+                write!(f, "target probability {} is not between 0 and 1", target)
+            }
+            BirthdayError::SearchTooLarge { limit } => {
+                write!(
+                    f,
+                    "the answer is above the search limit of {} people",
+                    limit
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for BirthdayError {}
+
+/// The most terms of the recurrence in `no_day_reaches` that
+/// [`BirthdayProblem::min_people`] computes for its largest group
+const MAX_SEARCH_WORK: u64 = 5_000_000;
+
+/// How birthdays are distributed over the days of a year
+#[derive(Debug, Clone, PartialEq)]
+pub struct BirthdayProblem {
+    /// The probability of each day; they add up to 1
+    probabilities: Vec<f64>,
+}
+
+impl BirthdayProblem {
+    /// Every day is equally likely
+    ///
+    /// Returns [`BirthdayError::NoDays`] if `days` is 0.
+    pub fn uniform(days: u32) -> Result<Self, BirthdayError> {
+        if days == 0 {
+            return Err(BirthdayError::NoDays);
+        }
+        Ok(BirthdayProblem {
+            probabilities: vec![1.0 / f64::from(days); days as usize],
+        })
+    }
+
+    /// Each day is as likely as its weight; the weights need not add up to 1
+    ///
+    /// Days with weight 0 are allowed and never chosen.
+    pub fn from_weights(weights: &[f64]) -> Result<Self, BirthdayError> {
+        if weights.is_empty() {
+            return Err(BirthdayError::NoDays);
+        }
+        if let Some((day, &weight)) = weights
+            .iter()
+            .enumerate()
+            .find(|(_, w)| !w.is_finite() || **w < 0.0)
+        {
+            return Err(BirthdayError::InvalidWeight { day, weight });
+        }
+        let total: f64 = weights.iter().sum();
+        if total == 0.0 {
+            return Err(BirthdayError::ZeroTotalWeight);
+        }
+        Ok(BirthdayProblem {
+            probabilities: weights.iter().map(|w| w / total).collect(),
+        })
+    }
+
+    /// The number of days in the year, including days with weight 0
+    pub fn days(&self) -> usize {
+        self.probabilities.len()
+    }
+
+    /// The probability that at least two of `n` people share a birthday
+    pub fn probability_shared(&self, n: u32) -> f64 {
+        self.probability_at_least(n, 2)
+    }
+
+    /// The probability that some day is the birthday of at least `k` of `n`
+    /// people
+    pub fn probability_at_least(&self, n: u32, k: u32) -> f64 {
+        // Exact, where the table below would carry rounding error
+        if n < k {
+            return 0.0;
+        }
+        let none = self.no_day_reaches(n, k);
+        (1.0 - none[n as usize]).clamp(0.0, 1.0)
+    }
+
+    /// The smallest number of people for which the probability that at least
+    /// `k` share a birthday is at least `target`
+    ///
+    /// The search never goes past the pigeonhole bound, where some day must
+    /// have `k` people, nor past the group size whose table would take more
+    /// than [`MAX_SEARCH_WORK`] terms to compute.
+    ///
+    /// # Errors
+    ///
+    /// [`BirthdayError::InvalidTarget`] if `target` is not within `0.0..=1.0`,
+    /// and [`BirthdayError::SearchTooLarge`] if the answer is beyond the
+    /// search limit.
+    pub fn min_people(&self, target: f64, k: u32) -> Result<u32, BirthdayError> {
+        if !(0.0..=1.0).contains(&target) {
+            return Err(BirthdayError::InvalidTarget(target));
+        }
+        if k == 0 {
+            return Ok(0);
+        }
+        // With this many people some day must have k of them
+        let days = self.probabilities.iter().filter(|&&p| p > 0.0).count() as u32;
+        let bound = days.saturating_mul(k - 1).saturating_add(1);
+        // Certainty comes only at the bound, though 1 - p rounds to 1 sooner
+        if target == 1.0 {
+            return Ok(bound);
+        }
+
+        let cap = bound.min(search_limit(days, k));
+        let mut limit = cap.min(64);
+        loop {
+            let none = self.no_day_reaches(limit, k);
+            if let Some(n) = none.iter().position(|p| 1.0 - p >= target) {
+                return Ok(n as u32);
+            }
+            if limit == cap {
+                return if cap == bound {
+                    Ok(bound)
+                } else {
+                    Err(BirthdayError::SearchTooLarge { limit: cap })
+                };
+            }
+            limit = limit.saturating_mul(2).min(cap);
+        }
+    }
+
+    /// Estimates [`probability_at_least`](Self::probability_at_least) by
+    /// drawing `trials` random groups of `n` people
+    ///
+    /// The same `seed` always gives the same estimate.
+    pub fn simulate(&self, n: u32, k: u32, trials: u32, seed: u64) -> f64 {
+        if trials == 0 {
+            return 0.0;
+        }
+        let cumulative: Vec<f64> = self
+            .probabilities
+            .iter()
+            .scan(0.0, |total, p| {
+                *total += p;
+                Some(*total)
+            })
+            .collect();
+        let last_day = self.days() - 1;
+
+        let mut rng = Rng::new(seed);
+        let mut counts = vec![0u32; self.days()];
+        let mut hits = 0;
+        for _ in 0..trials {
+            counts.fill(0);
+            let hit = k == 0
+                || (0..n).any(|_| {
+                    let u = rng.next_f64();
+                    // Rounding can leave the last total just below 1
+                    let day = cumulative.partition_point(|&c| c <= u).min(last_day);
+                    counts[day] += 1;
+                    counts[day] >= k
+                });
+            if hit {
+                hits += 1;
+            }
+        }
+        f64::from(hits) / f64::from(trials)
+    }
+
+    /// `result[m]` is the probability that no day has `k` or more of `m`
+    /// people, for every `m` up to `n`
+    ///
+    /// Goes through the days one at a time keeping `a[m]`, the probability
+    /// that `m` people are all born on the days seen so far with fewer than
+    /// `k` on each. A new day with probability `p` takes `j < k` of them:
+    /// `a'[m] = sum over j of C(m, j) p^j a[m - j]`. The binomials are
+    /// computed from log-factorials so that large `m` does not overflow.
+    fn no_day_reaches(&self, n: u32, k: u32) -> Vec<f64> {
+        let n = n as usize;
+        let k = k as usize;
+        let mut ln_factorial = vec![0.0; n + 1];
+        for m in 1..=n {
+            ln_factorial[m] = ln_factorial[m - 1] + (m as f64).ln();
+        }
+
+        let mut a = vec![0.0; n + 1];
+        if k == 0 {
+            return a;
+        }
+        a[0] = 1.0;
+        for &p in self.probabilities.iter().filter(|&&p| p > 0.0) {
+            let ln_p = p.ln();
+            let previous = a.clone();
+            for (m, value) in a.iter_mut().enumerate() {
+                *value = (0..k.min(m + 1))
+                    .map(|j| {
+                        let ln_binomial = ln_factorial[m] - ln_factorial[j] - ln_factorial[m - j];
+                        (ln_binomial + j as f64 * ln_p).exp() * previous[m - j]
+                    })
+                    .sum();
+            }
+        }
+        a
+    }
+}
+
+/// The largest group for which the table over `days` days takes at most
+/// [`MAX_SEARCH_WORK`] terms, about `days * n * min(n, k)`
+fn search_limit(days: u32, k: u32) -> u32 {
+    let per_day = MAX_SEARCH_WORK / u64::from(days.max(1));
+    let k = u64::from(k);
+    let n = if k * k <= per_day {
+        per_day / k
+    } else {
+        per_day.isqrt()
+    };
+    n.min(u64::from(u32::MAX)) as u32
+}
+
+/// The probability that at least two of `n` people share a birthday in a year
+/// of `days` equally likely days
+///
+/// Returns [`BirthdayError::NoDays`] if `days` is 0.
+pub fn shared_birthday_probability(n: u32, days: u32) -> Result<f64, BirthdayError> {
+    Ok(BirthdayProblem::uniform(days)?.probability_shared(n))
+}
+
+/// xorshift64*, enough for simulation and reproducible from a seed
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // The state must never be zero
+        Rng(seed ^ 0x9e37_79b9_7f4a_7c15 | 1)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Uniform in `0.0..1.0`
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -1,20 +1,12 @@
-pub fn new_birthday_probability(n: u32) -> f64 {
-    if n < 2 {
-        panic!("Input must be at least 2");
-    }
-
-    // Calculate probability that all birthdays are different
-    // Then subtract from 1 to get probability of at least one match
-    let mut different_probability = 1.0;
-    let days_in_year = 365.0;
+use crate::birthday::BirthdayProblem;
 
-    for i in 0..n {
-        different_probability *= (days_in_year - i as f64) / days_in_year;
-    }
-
-    // Probability of at least one shared birthday
-    let result = 1.0 - different_probability;
-    
-    // Round to 4 decimal places
-    (result * 10000.0).round() / 10000.0
-}
\ No newline at end of file
+/// The probability that at least two of `n` people share a birthday, over a
+/// 365-day year
+///
+/// Fewer than two people never share, so `n < 2` gives 0. The result is not
+/// rounded; format it as needed.
+pub fn new_birthday_probability(n: u32) -> f64 {
+    BirthdayProblem::uniform(365)
+        .expect("365 is not zero")
+        .probability_shared(n)
+}
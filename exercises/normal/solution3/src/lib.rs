@@ -0,0 +1,4 @@
+//! The birthday problem
+
+pub mod birthday;
+pub mod calc_logic;
@@ -1,4 +1,4 @@
-mod calc_logic;
+use solution3::calc_logic;
 
 fn main() {
     // 更改这个值来测试不同的人数
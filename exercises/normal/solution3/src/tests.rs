@@ -1,7 +1,9 @@
+mod birthday;
 mod calc_logic;
 
 #[cfg(test)]
 mod tests {
+    use super::birthday::{shared_birthday_probability, BirthdayError, BirthdayProblem};
     use super::calc_logic::new_birthday_probability;
 
     // 定义测试用例和预期结果
@@ -34,4 +36,76 @@ mod tests {
         println!("Total score: {:.2}", total_score);
         assert_eq!(100.00, total_score);
     }
+
+    #[test]
+    fn test_generalized_birthday() {
+        let year = BirthdayProblem::uniform(365).unwrap();
+        assert!((year.probability_shared(23) - 0.507_297_234).abs() < 1e-9);
+        assert!((new_birthday_probability(23) - 0.507_297_234).abs() < 1e-9);
+        // 不足两人时概率为 0，不再 panic
+        assert_eq!(new_birthday_probability(0), 0.0);
+        assert_eq!(new_birthday_probability(1), 0.0);
+        assert_eq!(shared_birthday_probability(366, 365), Ok(1.0));
+        assert_eq!(shared_birthday_probability(2, 1), Ok(1.0));
+        assert!((shared_birthday_probability(2, 2).unwrap() - 0.5).abs() < 1e-12);
+        assert_eq!(shared_birthday_probability(2, 0), Err(BirthdayError::NoDays));
+        assert_eq!(BirthdayProblem::uniform(0), Err(BirthdayError::NoDays));
+
+        // 三人同一天生日：87 人不到一半，88 人超过一半
+        assert!(year.probability_at_least(87, 3) < 0.5);
+        assert!(year.probability_at_least(88, 3) > 0.5);
+        assert_eq!(year.probability_at_least(731, 3), 1.0);
+        assert_eq!(year.probability_at_least(5, 1), 1.0);
+        assert_eq!(year.probability_at_least(0, 1), 0.0);
+
+        assert_eq!(year.min_people(0.5, 2), Ok(23));
+        assert_eq!(year.min_people(0.99, 2), Ok(57));
+        assert_eq!(year.min_people(0.5, 3), Ok(88));
+        assert_eq!(year.min_people(1.0, 2), Ok(366));
+        assert_eq!(year.min_people(0.0, 2), Ok(0));
+        assert_eq!(year.min_people(0.5, 0), Ok(0));
+        assert_eq!(year.min_people(1.5, 2), Err(BirthdayError::InvalidTarget(1.5)));
+        assert!(matches!(year.min_people(f64::NAN, 2), Err(BirthdayError::InvalidTarget(_))));
+        // k 很大时不会无休止地计算，而是在搜索上限处报错
+        assert_eq!(
+            year.min_people(0.99, 1_000_000),
+            Err(BirthdayError::SearchTooLarge { limit: 117 })
+        );
+        assert_eq!(year.min_people(1.0, 1_000_000), Ok(364_999_636));
+        // 只有一天时 k 个人必然同一天生日
+        let one_day = BirthdayProblem::uniform(1).unwrap();
+        assert_eq!(one_day.min_people(0.99, 3), Ok(3));
+
+        // 非均匀分布：两天的权重为 1:3
+        let skewed = BirthdayProblem::from_weights(&[1.0, 3.0]).unwrap();
+        assert!((skewed.probability_shared(2) - 0.625).abs() < 1e-12);
+        let with_gap = BirthdayProblem::from_weights(&[1.0, 0.0, 1.0]).unwrap();
+        assert_eq!(with_gap.days(), 3);
+        assert!((with_gap.probability_shared(2) - 0.5).abs() < 1e-12);
+        assert_eq!(with_gap.min_people(1.0, 2), Ok(3));
+
+        assert_eq!(BirthdayProblem::from_weights(&[]), Err(BirthdayError::NoDays));
+        assert_eq!(
+            BirthdayProblem::from_weights(&[1.0, -1.0]),
+            Err(BirthdayError::InvalidWeight { day: 1, weight: -1.0 })
+        );
+        assert_eq!(
+            BirthdayProblem::from_weights(&[0.0, 0.0]),
+            Err(BirthdayError::ZeroTotalWeight)
+        );
+
+        // 蒙特卡洛模拟与精确值相互印证
+        for (problem, n, k) in [(&year, 23, 2), (&year, 88, 3), (&skewed, 3, 2)] {
+            let exact = problem.probability_at_least(n, k);
+            let estimate = problem.simulate(n, k, 20_000, 2024);
+            assert!((estimate - exact).abs() < 0.02, "{n} {k}: {estimate} vs {exact}");
+            assert_eq!(estimate, problem.simulate(n, k, 20_000, 2024));
+        }
+        // 按生日月份加权，多数人集中在少数几天时更容易撞生日
+        let mut weights = vec![1.0; 365];
+        weights[..30].fill(5.0);
+        let seasonal = BirthdayProblem::from_weights(&weights).unwrap();
+        assert!(seasonal.probability_shared(23) > year.probability_shared(23));
+        assert!(seasonal.min_people(0.5, 2).unwrap() < 23);
+    }
 }
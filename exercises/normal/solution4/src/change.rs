@@ -0,0 +1,225 @@
+//! Making change from any set of bill denominations, optionally with a limited
+//! number of bills of each
+
+use std::fmt;
+
+/// Why a set of denominations was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeError {
+    /// No denominations were given
+    NoDenominations,
+    /// A bill worth 0 cannot make change
+    ZeroDenomination,
+    /// The same denomination was given twice
+    DuplicateDenomination(u32),
+}
+
+impl fmt::Display for ChangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeError::NoDenominations => write!(f, "no denominations"),
+            ChangeError::ZeroDenomination => write!(f, "denominations must be positive"),
+            ChangeError::DuplicateDenomination(value) => {
+                write!(f, "duplicate denomination: {}", value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ChangeError {}
+
+/// The bills handed out for an amount
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// (denomination, number of bills), largest denomination first; only
+    /// denominations that are used appear
+    pub bills: Vec<(u32, u32)>,
+}
+
+impl Change {
+    /// How many bills there are in total
+    pub fn count(&self) -> u32 {
+        self.bills.iter().map(|&(_, n)| n).sum()
+    }
+
+    /// The amount the bills add up to
+    pub fn total(&self) -> u64 {
+        self.bills
+            .iter()
+            .map(|&(value, n)| u64::from(value) * u64::from(n))
+            .sum()
+    }
+}
+
+/// Makes change from a fixed set of denominations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeMaker {
+    /// (denomination, how many bills are available), in ascending order;
+    /// `None` means there is no limit
+    denominations: Vec<(u32, Option<u32>)>,
+}
+
+impl ChangeMaker {
+    /// Any number of bills of each denomination may be used
+    pub fn new(denominations: &[u32]) -> Result<Self, ChangeError> {
+        Self::build(denominations.iter().map(|&value| (value, None)).collect())
+    }
+
+    /// At most `limit` bills of each `(denomination, limit)` may be used
+    pub fn with_limits(denominations: &[(u32, u32)]) -> Result<Self, ChangeError> {
+        Self::build(
+            denominations
+                .iter()
+                .map(|&(value, limit)| (value, Some(limit)))
+                .collect(),
+        )
+    }
+
+    fn build(mut denominations: Vec<(u32, Option<u32>)>) -> Result<Self, ChangeError> {
+        if denominations.is_empty() {
+            return Err(ChangeError::NoDenominations);
+        }
+        if denominations.iter().any(|&(value, _)| value == 0) {
+            return Err(ChangeError::ZeroDenomination);
+        }
+        denominations.sort_unstable();
+        if let Some(pair) = denominations.windows(2).find(|w| w[0].0 == w[1].0) {
+            return Err(ChangeError::DuplicateDenomination(pair[0].0));
+        }
+        Ok(ChangeMaker { denominations })
+    }
+
+    /// The denominations in ascending order
+    pub fn denominations(&self) -> Vec<u32> {
+        self.denominations.iter().map(|&(value, _)| value).collect()
+    }
+
+    /// The fewest bills that add up to `amount`
+    ///
+    /// # Returns
+    ///
+    /// `None` if no combination of the available bills makes `amount`.
+    pub fn make_change(&self, amount: u32) -> Option<Change> {
+        let amount = amount as usize;
+        let width = amount + 1;
+        let mut best: Vec<Option<u32>> = vec![None; width];
+        best[0] = Some(0);
+
+        // Limited denominations first: a 0/1 knapsack over their bundles.
+        // Bit `i * width + x` of `taken` records whether bundle i improved
+        // the best count for x
+        let items = self.bundles(amount);
+        let mut taken = vec![0u64; (items.len() * width).div_ceil(64)];
+        for (i, &(value, bills)) in items.iter().enumerate() {
+            let size = value as usize * bills as usize;
+            for x in (size..=amount).rev() {
+                let Some(rest) = best[x - size] else {
+                    continue;
+                };
+                if best[x].is_none_or(|current| rest + bills < current) {
+                    best[x] = Some(rest + bills);
+                    let bit = i * width + x;
+                    taken[bit / 64] |= 1 << (bit % 64);
+                }
+            }
+        }
+
+        // Then unlimited ones, one bill at a time. `last_bill[x]` is the
+        // bill that improved the best count for x, or 0 if none did
+        let mut last_bill = vec![0u32; width];
+        for x in 1..=amount {
+            for &(value, limit) in &self.denominations {
+                if limit.is_some() || value as usize > x {
+                    continue;
+                }
+                let Some(rest) = best[x - value as usize] else {
+                    continue;
+                };
+                if best[x].is_none_or(|current| rest + 1 < current) {
+                    best[x] = Some(rest + 1);
+                    last_bill[x] = value;
+                }
+            }
+        }
+        best[amount]?;
+
+        let mut counts = vec![0; self.denominations.len()];
+        let slot = |value: u32| self.denominations.partition_point(|&(d, _)| d < value);
+        let mut x = amount;
+        while last_bill[x] != 0 {
+            counts[slot(last_bill[x])] += 1;
+            x -= last_bill[x] as usize;
+        }
+        for (i, &(value, bills)) in items.iter().enumerate().rev() {
+            let bit = i * width + x;
+            if taken[bit / 64] & (1 << (bit % 64)) != 0 {
+                x -= value as usize * bills as usize;
+                counts[slot(value)] += bills;
+            }
+        }
+        let bills = self
+            .denominations
+            .iter()
+            .zip(counts)
+            .rev()
+            .filter(|&(_, n)| n > 0)
+            .map(|(&(value, _), n)| (value, n))
+            .collect();
+        Some(Change { bills })
+    }
+
+    /// The fewest bills that add up to `amount`, or `None` if it cannot be made
+    pub fn min_count(&self, amount: u32) -> Option<u32> {
+        self.make_change(amount).map(|change| change.count())
+    }
+
+    /// Counts the distinct multisets of bills that add up to `amount`
+    ///
+    /// The order of the bills does not matter. Returns `None` if the count
+    /// does not fit in a `u128`.
+    pub fn count_ways(&self, amount: u32) -> Option<u128> {
+        let amount = amount as usize;
+        let mut ways = vec![0u128; amount + 1];
+        ways[0] = 1;
+        for &(value, limit) in &self.denominations {
+            let value = value as usize;
+            // What limit + 1 bills of this denomination add up to
+            let excess = limit.and_then(|limit| value.checked_mul(limit as usize + 1));
+            let previous = ways.clone();
+            for x in value..=amount {
+                // ways[x - value] already allows up to `limit` bills of this
+                // denomination, so adding one more can reach limit + 1
+                let mut shifted = ways[x - value];
+                if let Some(excess) = excess.filter(|&excess| x >= excess) {
+                    shifted -= previous[x - excess];
+                }
+                ways[x] = previous[x].checked_add(shifted)?;
+            }
+        }
+        Some(ways[amount])
+    }
+
+    /// Splits each limited denomination into bundles of 1, 2, 4, ... bills
+    /// and a remainder, so that every count up to the limit is a sum of
+    /// bundles
+    ///
+    /// Limits are capped at the most bills `amount` could use.
+    fn bundles(&self, amount: usize) -> Vec<(u32, u32)> {
+        let mut bundles = Vec::new();
+        for &(value, limit) in &self.denominations {
+            let Some(limit) = limit else {
+                continue;
+            };
+            let usable = (amount / value as usize).min(u32::MAX as usize) as u32;
+            let mut left = limit.min(usable);
+            let mut size = 1;
+            while left > 0 {
+                let bills = size.min(left);
+                bundles.push((value, bills));
+                left -= bills;
+                size = size.saturating_mul(2);
+            }
+        }
+        bundles
+    }
+}
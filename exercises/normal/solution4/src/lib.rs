@@ -0,0 +1,4 @@
+//! Making change from bills
+
+pub mod change;
+pub mod rec_mc;
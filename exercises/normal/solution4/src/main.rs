@@ -1,4 +1,4 @@
-mod rec_mc;
+use solution4::rec_mc;
 
 fn main() {
     let amount = 93u32;
@@ -1,7 +1,9 @@
 // src/tests.rs
+mod change;
 mod rec_mc;
 #[cfg(test)]
 mod tests {
+    use super::change::{Change, ChangeError, ChangeMaker};
     use super::rec_mc::dp_rec_mc;
     // 定义测试用例和预期结果
     // const CASHES: [u32; 8] = [1, 2, 5, 10, 20, 30, 50, 100];
@@ -25,4 +27,81 @@ mod tests {
         println!("Total score: {:.2}", total_score);
         assert_eq!(100.00, total_score);
     }
+
+    // 暴力枚举各面额张数，统计组合数和最少张数
+    fn brute_force(bills: &[(u32, u32)], amount: u32) -> (u128, Option<u32>) {
+        match bills.split_first() {
+            None => (u128::from(amount == 0), (amount == 0).then_some(0)),
+            Some((&(value, limit), rest)) => {
+                let (mut ways, mut fewest) = (0, None::<u32>);
+                for n in (0..=limit).take_while(|n| n * value <= amount) {
+                    let (w, f) = brute_force(rest, amount - n * value);
+                    ways += w;
+                    if let Some(f) = f {
+                        fewest = Some(fewest.map_or(f + n, |best| best.min(f + n)));
+                    }
+                }
+                (ways, fewest)
+            }
+        }
+    }
+
+    #[test]
+    fn test_change_maker() {
+        let maker = ChangeMaker::new(&[1, 2, 5, 10, 20, 30, 50, 100]).unwrap();
+        let change = maker.make_change(93).unwrap();
+        assert_eq!(change.count(), 5);
+        assert_eq!(change.total(), 93);
+        assert_eq!(maker.make_change(0), Some(Change { bills: vec![] }));
+        assert_eq!(maker.min_count(40), Some(2));
+        // 不限张数的面额只需一维数组，大金额也不会占用大量内存
+        assert_eq!(dp_rec_mc(1_000_000), 10_000);
+        // 贪心会选 4+1+1，最优解是 3+3
+        let change = ChangeMaker::new(&[1, 3, 4]).unwrap().make_change(6).unwrap();
+        assert_eq!(change.bills, vec![(3, 2)]);
+
+        // 凑不出的金额返回 None，而不是一个巨大的张数
+        let maker = ChangeMaker::new(&[5, 3]).unwrap();
+        assert_eq!(maker.denominations(), vec![3, 5]);
+        assert_eq!(maker.make_change(7), None);
+        assert_eq!(maker.make_change(11), Some(Change { bills: vec![(5, 1), (3, 2)] }));
+        assert_eq!(maker.count_ways(7), Some(0));
+        assert_eq!(maker.count_ways(15), Some(2));
+
+        assert_eq!(ChangeMaker::new(&[1, 2, 5]).unwrap().count_ways(5), Some(4));
+        assert_eq!(ChangeMaker::new(&[]), Err(ChangeError::NoDenominations));
+        assert_eq!(ChangeMaker::new(&[0, 1]), Err(ChangeError::ZeroDenomination));
+        assert_eq!(ChangeMaker::new(&[2, 1, 2]), Err(ChangeError::DuplicateDenomination(2)));
+        // 组合数超过 u128 时返回 None
+        assert_eq!(ChangeMaker::new(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]).unwrap().count_ways(0), Some(1));
+        assert_eq!(ChangeMaker::new(&(1..=200).collect::<Vec<_>>()).unwrap().count_ways(2_000), None);
+
+        // 限量面额：只有一张 20 时 40 要用 30+10
+        let limited = ChangeMaker::with_limits(&[(10, 5), (20, 1), (30, 1)]).unwrap();
+        assert_eq!(limited.make_change(40).unwrap().bills, vec![(30, 1), (10, 1)]);
+        assert_eq!(limited.make_change(100), Some(Change { bills: vec![(30, 1), (20, 1), (10, 5)] }));
+        assert_eq!(limited.make_change(110), None);
+        assert_eq!(ChangeMaker::with_limits(&[(1, 0)]).unwrap().make_change(1), None);
+
+        // 与暴力枚举对比
+        let bills = [(1, 3), (3, 2), (4, 5), (7, 1), (10, 4)];
+        let limited = ChangeMaker::with_limits(&bills).unwrap();
+        let unlimited = ChangeMaker::new(&bills.map(|(value, _)| value)).unwrap();
+        for amount in 0..=80 {
+            let (ways, fewest) = brute_force(&bills, amount);
+            assert_eq!(limited.count_ways(amount), Some(ways), "{amount}");
+            assert_eq!(limited.min_count(amount), fewest, "{amount}");
+            if let Some(change) = limited.make_change(amount) {
+                assert_eq!(change.total(), u64::from(amount));
+                for (value, n) in change.bills {
+                    let &(_, limit) = bills.iter().find(|b| b.0 == value).unwrap();
+                    assert!(n <= limit, "{amount}");
+                }
+            }
+
+            let (ways, fewest) = brute_force(&bills.map(|(value, _)| (value, amount)), amount);
+            assert_eq!(unlimited.count_ways(amount), Some(ways), "{amount}");
+            assert_eq!(unlimited.min_count(amount), fewest, "{amount}");
+        }
+    }
 }
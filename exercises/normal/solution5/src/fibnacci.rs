@@ -1,24 +1,12 @@
+use crate::fibonacci;
+
+/// Sums the odd Fibonacci numbers up to and including `threshold`, counting
+/// the leading 1 twice (1 + 1 + 3 + 5 + ...)
+///
+/// # Panics
+///
+/// Panics if the sum does not fit in a `u32`; use [`fibonacci::sum_odd`]
+/// with a wider type for such thresholds.
 pub fn odd_fibnacci_sum(threshold: u32) -> u32 {
-    if threshold < 1 {
-        return 0;
-    }
-    
-    let mut sum = 0;
-    let mut a = 1; // First Fibonacci number
-    let mut b = 1; // Second Fibonacci number
-    
-    // Process Fibonacci numbers until we reach the threshold
-    while a <= threshold {
-        // If current Fibonacci number is odd, add it to sum
-        if a % 2 == 1 {
-            sum += a;
-        }
-        
-        // Generate next Fibonacci number
-        let next = a + b;
-        a = b;
-        b = next;
-    }
-    
-    sum
+    fibonacci::sum_odd(&threshold).expect("the sum overflows u32")
 }
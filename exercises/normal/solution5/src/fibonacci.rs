@@ -0,0 +1,291 @@
+//! Fibonacci numbers over any integer type, with overflow detection
+//!
+//! F(0) = 0, F(1) = 1 and F(n) = F(n - 1) + F(n - 2). Everything here is
+//! generic over [`FibNum`], which is implemented for the primitive integers
+//! and for [`BigUint`], which never overflows.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+/// The arithmetic the Fibonacci routines need from a number type
+pub trait FibNum: Clone + PartialOrd {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+    /// `None` if the result does not fit in the type; for unsigned types and
+    /// [`BigUint`] that includes every negative result
+    fn checked_sub(&self, other: &Self) -> Option<Self>;
+    fn checked_mul(&self, other: &Self) -> Option<Self>;
+    /// The remainder after dividing by `k`, for non-negative values
+    fn rem_u32(&self, k: u32) -> u32;
+}
+
+/// `$wide` holds every `$t` as well as every `u32`, for the remainder
+macro_rules! impl_fib_num {
+    ($($t:ty => $wide:ty),*) => {$(
+        impl FibNum for $t {
+            fn zero() -> Self {
+                0
+            }
+
+            fn one() -> Self {
+                1
+            }
+
+            fn checked_add(&self, other: &Self) -> Option<Self> {
+                <$t>::checked_add(*self, *other)
+            }
+
+            fn checked_sub(&self, other: &Self) -> Option<Self> {
+                <$t>::checked_sub(*self, *other)
+            }
+
+            fn checked_mul(&self, other: &Self) -> Option<Self> {
+                <$t>::checked_mul(*self, *other)
+            }
+
+            fn rem_u32(&self, k: u32) -> u32 {
+                <$wide>::from(*self).rem_euclid(<$wide>::from(k)) as u32
+            }
+        }
+    )*};
+}
+
+impl_fib_num!(u32 => u64, u64 => u128, u128 => u128, i32 => i64, i64 => i128);
+
+/// An unsigned integer of any size, enough for Fibonacci numbers
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Base 2^32 digits, least significant first, with no trailing zeros
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    fn normalized(mut limbs: Vec<u32>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigUint { limbs }
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(n: u64) -> Self {
+        BigUint::normalized(vec![n as u32, (n >> 32) as u32])
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FibNum for BigUint {
+    fn zero() -> Self {
+        BigUint { limbs: Vec::new() }
+    }
+
+    fn one() -> Self {
+        BigUint { limbs: vec![1] }
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        let len = self.limbs.len().max(other.limbs.len());
+        let mut limbs = Vec::with_capacity(len + 1);
+        let mut carry = 0u64;
+        for i in 0..len {
+            let sum = u64::from(*self.limbs.get(i).unwrap_or(&0))
+                + u64::from(*other.limbs.get(i).unwrap_or(&0))
+                + carry;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+        limbs.push(carry as u32);
+        Some(BigUint::normalized(limbs))
+    }
+
+    fn checked_sub(&self, other: &Self) -> Option<Self> {
+        if *self < *other {
+            return None;
+        }
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0i64;
+        for (i, &limb) in self.limbs.iter().enumerate() {
+            let mut diff = i64::from(limb) - i64::from(*other.limbs.get(i).unwrap_or(&0)) - borrow;
+            borrow = 0;
+            if diff < 0 {
+                diff += 1 << 32;
+                borrow = 1;
+            }
+            limbs.push(diff as u32);
+        }
+        Some(BigUint::normalized(limbs))
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let t = u64::from(a) * u64::from(b) + u64::from(limbs[i + j]) + carry;
+                limbs[i + j] = t as u32;
+                carry = t >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        Some(BigUint::normalized(limbs))
+    }
+
+    fn rem_u32(&self, k: u32) -> u32 {
+        self.limbs.iter().rev().fold(0u64, |rem, &limb| {
+            ((rem << 32) | u64::from(limb)) % u64::from(k)
+        }) as u32
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Peel off base 10^9 chunks, least significant first
+        const CHUNK: u64 = 1_000_000_000;
+        let mut limbs = self.limbs.clone();
+        let mut chunks = Vec::new();
+        while !limbs.is_empty() {
+            let mut rem = 0u64;
+            for limb in limbs.iter_mut().rev() {
+                let value = (rem << 32) | u64::from(*limb);
+                *limb = (value / CHUNK) as u32;
+                rem = value % CHUNK;
+            }
+            chunks.push(rem);
+            while limbs.last() == Some(&0) {
+                limbs.pop();
+            }
+        }
+
+        let mut s = chunks.pop().unwrap_or(0).to_string();
+        for chunk in chunks.iter().rev() {
+            s.push_str(&format!("{:09}", chunk));
+        }
+        f.pad_integral(true, "", &s)
+    }
+}
+
+/// Iterator over F(0), F(1), F(2), ... that ends before the first term that
+/// does not fit in `T`
+#[derive(Debug, Clone)]
+pub struct Fibonacci<T> {
+    current: Option<T>,
+    next: Option<T>,
+}
+
+impl<T: FibNum> Iterator for Fibonacci<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let term = self.current.take()?;
+        self.current = self.next.take();
+        self.next = match self.current {
+            Some(ref current) => term.checked_add(current),
+            None => None,
+        };
+        Some(term)
+    }
+}
+
+/// Returns an iterator over the Fibonacci numbers that fit in `T`
+pub fn fibonacci<T: FibNum>() -> Fibonacci<T> {
+    Fibonacci {
+        current: Some(T::zero()),
+        next: Some(T::one()),
+    }
+}
+
+/// Computes F(n) by fast doubling, in O(log n) multiplications
+///
+/// # Returns
+///
+/// `None` if F(n) does not fit in `T`.
+pub fn checked_nth<T: FibNum>(n: u64) -> Option<T> {
+    // (a, b) = (F(k), F(k + 1)) for k = the bits of n seen so far; then
+    // F(2k) = F(k) * (2F(k + 1) - F(k)) and F(2k + 1) = F(k)^2 + F(k + 1)^2
+    let (mut a, mut b) = (T::zero(), T::one());
+    for bit in (0..64 - n.leading_zeros()).rev() {
+        let odd = (n >> bit) & 1 == 1;
+        // 2F(k + 1) - F(k) = F(k + 1) + F(k - 1), which cannot overflow
+        // unless F(2k) does
+        let double_even = || a.checked_mul(&b.checked_add(&b.checked_sub(&a)?)?);
+        let double_odd = || a.checked_mul(&a)?.checked_add(&b.checked_mul(&b)?);
+        if bit == 0 {
+            // F(n + 1) may not fit even when F(n) does, so skip it
+            return if odd { double_odd() } else { double_even() };
+        }
+        let (c, d) = (double_even()?, double_odd()?);
+        if odd {
+            let next = c.checked_add(&d)?;
+            a = d;
+            b = next;
+        } else {
+            a = c;
+            b = d;
+        }
+    }
+    Some(a)
+}
+
+/// Computes F(n) by fast doubling
+///
+/// # Panics
+///
+/// Panics if F(n) does not fit in `T`; see [`checked_nth`].
+pub fn nth<T: FibNum>(n: u64) -> T {
+    checked_nth(n).expect("the Fibonacci number overflows")
+}
+
+/// Sums the Fibonacci numbers up to and including `threshold` that satisfy
+/// `predicate`
+///
+/// The sequence starts 0, 1, 1, 2, ..., so 1 is counted twice if it matches.
+///
+/// # Returns
+///
+/// `None` if the sum does not fit in `T`.
+pub fn sum_where<T: FibNum, P: Fn(&T) -> bool>(threshold: &T, predicate: P) -> Option<T> {
+    let mut sum = T::zero();
+    for term in fibonacci::<T>().take_while(|term| term <= threshold) {
+        if predicate(&term) {
+            sum = sum.checked_add(&term)?;
+        }
+    }
+    Some(sum)
+}
+
+/// Sums the odd Fibonacci numbers up to and including `threshold`
+pub fn sum_odd<T: FibNum>(threshold: &T) -> Option<T> {
+    sum_where(threshold, |term| term.rem_u32(2) == 1)
+}
+
+/// Sums the even Fibonacci numbers up to and including `threshold`
+pub fn sum_even<T: FibNum>(threshold: &T) -> Option<T> {
+    sum_where(threshold, |term| term.rem_u32(2) == 0)
+}
+
+/// Sums the Fibonacci numbers up to and including `threshold` that are
+/// multiples of `k`
+///
+/// # Panics
+///
+/// Panics if `k` is 0.
+pub fn sum_multiples_of<T: FibNum>(threshold: &T, k: u32) -> Option<T> {
+    assert!(k > 0, "k must be positive");
+    sum_where(threshold, |term| term.rem_u32(k) == 0)
+}
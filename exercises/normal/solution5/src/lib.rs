@@ -0,0 +1,4 @@
+//! Fibonacci numbers and their sums
+
+pub mod fibnacci;
+pub mod fibonacci;
@@ -1,4 +1,4 @@
-mod fibnacci;
+use solution5::fibnacci;
 
 fn main() {
     let num = 20;
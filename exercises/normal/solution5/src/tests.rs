@@ -1,9 +1,13 @@
 
 // src/tests.rs
 mod fibnacci;
+mod fibonacci;
 #[cfg(test)]
 mod tests {
     use super::fibnacci::odd_fibnacci_sum;
+    use super::fibonacci::{
+        checked_nth, fibonacci, nth, sum_even, sum_multiples_of, sum_odd, sum_where, BigUint,
+    };
     // 定义测试用例和预期结果
     const TEST_CASES: &[(u32, u32)] = &[
         (20, 23),
@@ -25,4 +29,49 @@ mod tests {
         println!("Total score: {:.2}", total_score);
         assert_eq!(100.00, total_score);
     }
+
+    #[test]
+    fn test_fibonacci_engine() {
+        let first: Vec<u32> = fibonacci().take(10).collect();
+        assert_eq!(first, vec![0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+        // 迭代器在溢出前停止：u32 最多到 F(47)，i32 到 F(46)，u128 到 F(186)
+        assert_eq!(fibonacci::<u32>().count(), 48);
+        assert_eq!(fibonacci::<i32>().last(), Some(1_836_311_903));
+        assert_eq!(fibonacci::<u64>().count(), 94);
+        assert_eq!(fibonacci::<u128>().count(), 187);
+
+        // 快速倍增与逐项迭代一致，边界项不会误报溢出
+        for (n, term) in fibonacci::<u128>().enumerate() {
+            assert_eq!(checked_nth::<u128>(n as u64), Some(term), "{n}");
+        }
+        assert_eq!(checked_nth::<u32>(47), Some(2_971_215_073));
+        assert_eq!(checked_nth::<u32>(48), None);
+        assert_eq!(checked_nth::<i32>(46), Some(1_836_311_903));
+        assert_eq!(checked_nth::<i32>(47), None);
+        assert_eq!(checked_nth::<u128>(187), None);
+        assert_eq!(nth::<u64>(93), 12_200_160_415_121_876_738);
+
+        // 大整数
+        let f100: BigUint = nth(100);
+        assert_eq!(f100.to_string(), "354224848179261915075");
+        assert_eq!(nth::<BigUint>(186).to_string(), nth::<u128>(186).to_string());
+        let f1000 = nth::<BigUint>(1000).to_string();
+        assert_eq!(f1000.len(), 209);
+        assert!(f1000.starts_with("43466557686937456435688527675040625802564660517371780402481729089536555417949051890403879840079255169295922593080322634775209689623239873322471161642996440906533187938298969649928516003704476137795166849228875"));
+        assert_eq!(fibonacci::<BigUint>().nth(300), Some(nth(300)));
+        assert_eq!(BigUint::from(0).to_string(), "0");
+        assert_eq!(format!("{:>5}", BigUint::from(42)), "   42");
+
+        // 按条件求和
+        assert_eq!(sum_odd(&20u32), Some(23));
+        assert_eq!(sum_even(&4_000_000u64), Some(4_613_732));
+        assert_eq!(sum_multiples_of(&100u32, 3), Some(3 + 21));
+        assert_eq!(sum_where(&10u32, |&x| x > 4), Some(5 + 8));
+        assert_eq!(sum_odd(&u32::MAX), None);
+        assert!(sum_odd(&u64::from(u32::MAX)).unwrap() > u64::from(u32::MAX));
+        assert_eq!(
+            sum_even(&BigUint::from(4_000_000)),
+            Some(BigUint::from(4_613_732))
+        );
+    }
 }
@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fs::File;
+
+// 统计不稳定习题时保留的最近评测次数
+pub const HISTORY_RUNS: usize = 10;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FlakySummary {
+    pub name: String,
+    // 最近几次评测中需要重试才通过的次数
+    pub flaky_runs: usize,
+}
+
+// 上一次的报告中只关心不稳定历史，其余字段忽略
+#[derive(Deserialize)]
+struct PreviousReport {
+    #[serde(default)]
+    flaky_history: Vec<Vec<String>>,
+}
+
+// 从上一次的评测报告中读取不稳定历史，文件不存在或格式不符时视为没有历史
+pub fn load_history(file_path: &str) -> Vec<Vec<String>> {
+    File::open(file_path)
+        .ok()
+        .and_then(|file| serde_json::from_reader::<_, PreviousReport>(file).ok())
+        .map(|report| report.flaky_history)
+        .unwrap_or_default()
+}
+
+// 记录本次评测中的不稳定习题，只保留最近 HISTORY_RUNS 次
+pub fn record_run(history: &mut Vec<Vec<String>>, flaky: Vec<String>) {
+    history.push(flaky);
+    if history.len() > HISTORY_RUNS {
+        let excess = history.len() - HISTORY_RUNS;
+        history.drain(..excess);
+    }
+}
+
+// 汇总最近几次评测中出现过重试的习题，按出现次数从多到少排序
+pub fn summarize(history: &[Vec<String>]) -> Vec<FlakySummary> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for run in history {
+        for name in run {
+            *counts.entry(name).or_default() += 1;
+        }
+    }
+
+    let mut summary: Vec<FlakySummary> = counts
+        .into_iter()
+        .map(|(name, flaky_runs)| FlakySummary {
+            name: name.to_string(),
+            flaky_runs,
+        })
+        .collect();
+    summary.sort_by_key(|entry| Reverse(entry.flaky_runs));
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn counts(history: &[Vec<String>]) -> Vec<(String, usize)> {
+        summarize(history)
+            .into_iter()
+            .map(|entry| (entry.name, entry.flaky_runs))
+            .collect()
+    }
+
+    #[test]
+    fn test_record_run_keeps_recent_runs() {
+        let mut history = Vec::new();
+        for run in 0..HISTORY_RUNS + 3 {
+            record_run(&mut history, names(&[&run.to_string()]));
+        }
+        assert_eq!(history.len(), HISTORY_RUNS);
+        assert_eq!(history[0], names(&["3"]));
+        assert_eq!(history[HISTORY_RUNS - 1], names(&[&(HISTORY_RUNS + 2).to_string()]));
+    }
+
+    #[test]
+    fn test_summarize_across_runs() {
+        let mut history = Vec::new();
+        record_run(&mut history, names(&["b", "a"]));
+        record_run(&mut history, Vec::new());
+        record_run(&mut history, names(&["a", "c"]));
+        record_run(&mut history, names(&["c", "a"]));
+
+        assert_eq!(
+            counts(&history),
+            [("a".to_string(), 3), ("c".to_string(), 2), ("b".to_string(), 1)]
+        );
+        assert!(summarize(&[]).is_empty());
+
+        // 超出保留次数的评测不再计入
+        for _ in 0..HISTORY_RUNS {
+            record_run(&mut history, names(&["c"]));
+        }
+        assert_eq!(counts(&history), [("c".to_string(), HISTORY_RUNS)]);
+    }
+
+    #[test]
+    fn test_load_history() {
+        let dir = std::env::temp_dir().join(format!("flaky-history-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("report.json");
+        let path = path.to_str().unwrap();
+
+        // 文件不存在、不是 JSON 或没有不稳定历史时视为没有历史
+        assert!(load_history(path).is_empty());
+        fs::write(path, "{ not json").unwrap();
+        assert!(load_history(path).is_empty());
+        fs::write(path, r#"{"exercises": [], "statistics": {}}"#).unwrap();
+        assert!(load_history(path).is_empty());
+
+        // 其余字段不影响读取
+        fs::write(
+            path,
+            r#"{"exercises": [], "flaky": [], "flaky_history": [["a"], [], ["a", "b"]]}"#,
+        )
+        .unwrap();
+        let mut history = load_history(path);
+        assert_eq!(history, [names(&["a"]), Vec::new(), names(&["a", "b"])]);
+
+        record_run(&mut history, names(&["b"]));
+        assert_eq!(counts(&history), [("a".to_string(), 2), ("b".to_string(), 2)]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
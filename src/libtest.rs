@@ -0,0 +1,251 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::process::Command;
+
+// 让 libtest 以 JSON 格式逐行输出测试事件，并附带每个测试的耗时
+const JSON_ARGS: [&str; 5] = ["-Z", "unstable-options", "--format", "json", "--report-time"];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TestCaseResult {
+    pub name: String,
+    pub result: bool,
+    pub duration_ms: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub panic_message: Option<String>,
+}
+
+// 探测 JSON 支持时使用的过滤条件，不会匹配任何测试
+const PROBE_FILTER: &str = "__libtest_json_probe__";
+
+// 让测试二进制输出 JSON 格式。稳定版工具链需要 RUSTC_BOOTSTRAP 才接受 -Z 参数，
+// 它只设置在测试二进制上，不影响编译；关闭回溯，避免 panic 信息混入调用栈
+pub fn enable_json_output(command: &mut Command) {
+    command
+        .args(JSON_ARGS)
+        .env("RUSTC_BOOTSTRAP", "1")
+        .env("RUST_BACKTRACE", "0");
+}
+
+// 以 JSON 格式运行测试二进制但不执行任何测试，检查工具链是否支持 JSON 输出
+pub fn supports_json_output(mut command: Command) -> bool {
+    enable_json_output(&mut command);
+    command.args(["--exact", PROBE_FILTER]);
+    match command.output() {
+        Ok(output) => {
+            output.status.success()
+                && parse_json_output(&String::from_utf8_lossy(&output.stdout)).is_some()
+        }
+        Err(_) => false,
+    }
+}
+
+// 解析 libtest 的 JSON 输出；没有任何测试套件事件时返回 None（例如工具链不支持 JSON 格式）
+pub fn parse_json_output(stdout: &str) -> Option<Vec<TestCaseResult>> {
+    let mut saw_suite = false;
+    let mut cases = Vec::new();
+
+    for line in stdout.lines() {
+        let event: Value = match serde_json::from_str(line.trim()) {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+
+        match event["type"].as_str() {
+            Some("suite") => saw_suite = true,
+            Some("test") => {
+                let result = match event["event"].as_str() {
+                    Some("ok") => true,
+                    Some("failed") | Some("timeout") => false,
+                    _ => continue,
+                };
+                let name = match event["name"].as_str() {
+                    Some(name) => name.to_string(),
+                    None => continue,
+                };
+                let duration_ms = event["exec_time"].as_f64().unwrap_or(0.0) * 1000.0;
+                let panic_message = if result {
+                    None
+                } else {
+                    event["stdout"].as_str().and_then(extract_panic_message)
+                };
+
+                cases.push(TestCaseResult {
+                    name,
+                    result,
+                    duration_ms,
+                    panic_message,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    if saw_suite {
+        Some(cases)
+    } else {
+        None
+    }
+}
+
+// 解析 libtest 的普通输出（"test 名称 ... ok" 形式的行），没有耗时与 panic 信息
+pub fn parse_plain_output(stdout: &str) -> Vec<TestCaseResult> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (name, status) = line.strip_prefix("test ")?.rsplit_once(" ... ")?;
+            let result = match status.trim() {
+                "ok" => true,
+                "FAILED" => false,
+                _ => return None,
+            };
+            Some(TestCaseResult {
+                name: name.to_string(),
+                result,
+                duration_ms: 0.0,
+                panic_message: None,
+            })
+        })
+        .collect()
+}
+
+// 从失败测试的输出中提取 panic 信息（"panicked at 位置:" 之后到提示行之前的内容）
+fn extract_panic_message(output: &str) -> Option<String> {
+    let mut lines = output.lines().skip_while(|line| !line.contains("panicked at"));
+    let header = lines.next()?;
+
+    // 旧版本工具链把信息写在同一行：panicked at 'msg', src/lib.rs:1:1
+    let mut message: Vec<&str> = Vec::new();
+    if let Some((_, rest)) = header.split_once("panicked at '") {
+        if let Some((msg, _)) = rest.rsplit_once("', ") {
+            message.push(msg);
+        }
+    }
+
+    message.extend(
+        lines.take_while(|line| !line.starts_with("note:") && !line.starts_with("stack backtrace:")),
+    );
+
+    let message = message.join("\n").trim().to_string();
+    if message.is_empty() {
+        None
+    } else {
+        Some(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAILED_STDOUT: &str = "\nthread 'tests::b' panicked at src/lib.rs:3:5:\nassertion `left == right` failed\n  left: 1\n right: 2\nnote: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n";
+
+    fn json_output() -> String {
+        [
+            r#"{ "type": "suite", "event": "started", "test_count": 3 }"#.to_string(),
+            r#"{ "type": "test", "event": "started", "name": "tests::a" }"#.to_string(),
+            r#"{ "type": "test", "name": "tests::a", "event": "ok", "exec_time": 0.0015 }"#
+                .to_string(),
+            serde_json::json!({
+                "type": "test",
+                "name": "tests::b",
+                "event": "failed",
+                "exec_time": 0.25,
+                "stdout": FAILED_STDOUT,
+            })
+            .to_string(),
+            r#"{ "type": "test", "name": "tests::c", "event": "ignored" }"#.to_string(),
+            r#"{ "type": "suite", "event": "failed", "passed": 1, "failed": 1 }"#.to_string(),
+        ]
+        .join("\n")
+    }
+
+    #[test]
+    fn test_parse_json_output() {
+        let cases = parse_json_output(&json_output()).unwrap();
+        assert_eq!(cases.len(), 2);
+        assert_eq!(cases[0].name, "tests::a");
+        assert!(cases[0].result);
+        assert!((cases[0].duration_ms - 1.5).abs() < 1e-9);
+        assert_eq!(cases[0].panic_message, None);
+        assert_eq!(cases[1].name, "tests::b");
+        assert!(!cases[1].result);
+        assert!((cases[1].duration_ms - 250.0).abs() < 1e-9);
+        assert_eq!(
+            cases[1].panic_message.as_deref(),
+            Some("assertion `left == right` failed\n  left: 1\n right: 2")
+        );
+    }
+
+    #[test]
+    fn test_parse_json_output_truncated_or_garbage() {
+        // 输出在一行中间被截断时，保留之前完整的事件
+        let output = json_output();
+        let truncated = &output[..output.find("tests::b").unwrap()];
+        let cases = parse_json_output(truncated).unwrap();
+        assert_eq!(cases.len(), 1);
+        assert_eq!(cases[0].name, "tests::a");
+
+        // 不是 JSON，或者没有测试套件事件时视为不支持 JSON 输出
+        assert!(parse_json_output("").is_none());
+        assert!(parse_json_output("running 1 test\ntest tests::a ... ok\n").is_none());
+        assert!(
+            parse_json_output("{\"type\": \"test\", \"name\": \"x\", \"event\": \"ok\"}").is_none()
+        );
+        assert!(parse_json_output("\u{0}\u{ff}{{{ \"type\": ").is_none());
+    }
+
+    #[test]
+    fn test_parse_plain_output() {
+        let output = "\nrunning 4 tests\ntest tests::a ... ok\ntest tests::b ... FAILED\n\
+                      test tests::c ... ignored\ntest src/lib.rs - f (line 3) ... ok\n\
+                      test tests::d ... \n\nfailures:\n\ntest result: FAILED. 2 passed; 1 failed\n";
+        let cases = parse_plain_output(output);
+        let summary: Vec<(&str, bool)> = cases
+            .iter()
+            .map(|case| (case.name.as_str(), case.result))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("tests::a", true),
+                ("tests::b", false),
+                ("src/lib.rs - f (line 3)", true)
+            ]
+        );
+        assert!(parse_plain_output("").is_empty());
+        assert!(parse_plain_output("garbage\u{0}\ntest ... \n").is_empty());
+    }
+
+    #[test]
+    fn test_extract_panic_message() {
+        assert_eq!(
+            extract_panic_message(FAILED_STDOUT).as_deref(),
+            Some("assertion `left == right` failed\n  left: 1\n right: 2")
+        );
+        // 旧版本工具链的单行格式
+        assert_eq!(
+            extract_panic_message(
+                "thread 'main' panicked at 'boom', src/main.rs:1:1\nnote: run with ..."
+            )
+            .as_deref(),
+            Some("boom")
+        );
+        // 回溯不计入 panic 信息
+        assert_eq!(
+            extract_panic_message(
+                "thread 't' panicked at src/a.rs:1:1:\noops\nstack backtrace:\n   0: f\n"
+            )
+            .as_deref(),
+            Some("oops")
+        );
+        assert_eq!(
+            extract_panic_message("printed output\nno panic here\n"),
+            None
+        );
+        assert_eq!(
+            extract_panic_message("thread 't' panicked at src/a.rs:1:1:\nnote: ...\n"),
+            None
+        );
+        assert_eq!(extract_panic_message(""), None);
+    }
+}
@@ -1,9 +1,28 @@
+mod flaky;
+mod libtest;
+
+use flaky::FlakySummary;
+use libtest::TestCaseResult;
 use serde::{Deserialize, Serialize};
 use std::fs::{self, File};
+use std::hint::black_box;
 use std::path::{Path, PathBuf};
 use std::process::{Command, exit};
+use std::sync::OnceLock;
 use std::time::Instant;
-use std::io::{self, Write};
+use std::io;
+
+// 测试代码通过该环境变量读取时间限制的放大倍数
+const TIME_BUDGET_ENV: &str = "TIME_BUDGET_SCALE";
+// 校准任务在参考机器（GitHub Actions ubuntu-latest，debug 构建）上的耗时（毫秒），
+// exercise_config.json 未设置 calibration_reference_ms 时使用。
+// 更换参考机器后，在新机器上运行 `cargo run calibrate` 并把输出的耗时写入配置文件
+const DEFAULT_CALIBRATION_REFERENCE_MS: f64 = 40.0;
+// 校准任务重复次数，取最快的一次以减少抖动
+const CALIBRATION_ROUNDS: usize = 3;
+
+// 测试二进制是否支持 JSON 输出，第一次运行测试时检测，之后沿用
+static JSON_OUTPUT: OnceLock<bool> = OnceLock::new();
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct Exercise {
@@ -12,6 +31,9 @@ struct Exercise {
     #[serde(rename = "type")]
     exercise_type: String,  
     score: i32, 
+    // 测试中含有时间限制的习题，开启 --release 时以 release 模式构建
+    #[serde(default)]
+    perf_gated: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -19,13 +41,84 @@ struct ExerciseConfig {
     easy: Vec<Exercise>,
     normal: Vec<Exercise>,
     hard: Vec<Exercise>,
+    #[serde(default)]
+    weights: TierWeights,
+    // 校准任务在参考机器上的耗时（毫秒），本机耗时与之相比得到时间限制的放大倍数
+    #[serde(default = "default_calibration_reference_ms")]
+    calibration_reference_ms: f64,
+}
+
+fn default_calibration_reference_ms() -> f64 {
+    DEFAULT_CALIBRATION_REFERENCE_MS
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+// 各难度在总分中所占的分值，默认与 README 中的说明一致
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+struct TierWeights {
+    easy: i32,
+    normal: i32,
+    hard: i32,
+}
+
+impl Default for TierWeights {
+    fn default() -> Self {
+        TierWeights {
+            easy: 20,
+            normal: 30,
+            hard: 50,
+        }
+    }
+}
+
+impl TierWeights {
+    fn get(&self, difficulty: Difficulty) -> i32 {
+        match difficulty {
+            Difficulty::Easy => self.easy,
+            Difficulty::Normal => self.normal,
+            Difficulty::Hard => self.hard,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 struct ExerciseResult {
     name: String,
+    path: String,
+    #[serde(rename = "type")]
+    exercise_type: String,
+    difficulty: Difficulty,
     result: bool,
     score: i32, 
+    max_score: i32,
+    status: ExerciseStatus,
+    #[serde(default)]
+    attempts: Vec<Attempt>,
+    #[serde(default)]
+    tests: Vec<TestCaseResult>,
+}
+
+// 习题状态：重试后才通过的记为 flaky
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum ExerciseStatus {
+    Passed,
+    Failed,
+    Flaky,
+}
+
+// 测试阶段的一次运行
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Attempt {
+    result: bool,
+    duration_ms: f64,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -33,27 +126,80 @@ struct Statistics {
     total_exercises: usize,
     total_successes: usize,
     total_failures: usize,
+    total_flaky: usize,
     total_score: i32,  
+    max_total_score: i32,
     total_time: u64,
+    time_budget_scale: f64,
+    // 按难度分值加权后的得分及其占满分的百分比
+    weighted_score: f64,
+    percentage: f64,
+    difficulties: Vec<DifficultyStatistics>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct DifficultyStatistics {
+    difficulty: Difficulty,
+    total_exercises: usize,
+    total_successes: usize,
+    total_failures: usize,
+    score: i32,
+    max_score: i32,
+    weight: i32,
+    weighted_score: f64,
+    percentage: f64,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 struct Report {
     exercises: Vec<ExerciseResult>,
     statistics: Statistics,
+    flaky: Vec<FlakySummary>,
+    flaky_history: Vec<Vec<String>>,
+}
+
+#[derive(Debug)]
+struct Options {
+    mode: String,
+    release: bool,
+    time_scale: Option<f64>,
+    retries: u32,
+}
+
+#[derive(Debug)]
+struct RunSettings {
+    release: bool,
+    time_scale: f64,
+    retries: u32,
+}
+
+// 单个习题的评测结果：是否通过、测试阶段每次运行的耗时，以及最后一次运行中每个测试用例的结果
+#[derive(Debug, Default)]
+struct ExerciseOutcome {
+    passed: bool,
+    attempts: Vec<Attempt>,
+    tests: Vec<TestCaseResult>,
 }
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
-    if args.len() < 2 {
-        eprintln!("Please provide a command: 'watch' or 'all'");
-        exit(1);
+    let options = match parse_options(&args[1..]) {
+        Ok(opts) => opts,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("Usage: cargo run <watch|all|calibrate> [--release] [--time-scale <factor>] [--retries <n>]");
+            exit(1);
+        }
+    };
+
+    // 只测量本机的校准耗时，用于在新的参考机器上更新 calibration_reference_ms
+    if options.mode == "calibrate" {
+        println!("Calibration workload: {:.1} ms", measure_calibration_ms());
+        return;
     }
 
-    let mode = &args[1];
     let start_time = Instant::now();
 
-    
     let config = match load_exercise_config("exercise_config.json") {
         Ok(cfg) => cfg,
         Err(e) => {
@@ -61,6 +207,29 @@ fn main() {
             exit(1);
         }
     };
+    if !config.calibration_reference_ms.is_finite() || config.calibration_reference_ms <= 0.0 {
+        eprintln!(
+            "Invalid calibration_reference_ms in config file: {}",
+            config.calibration_reference_ms
+        );
+        exit(1);
+    }
+
+    let time_scale = match options.time_scale {
+        Some(scale) => scale,
+        None => calibrate_time_scale(config.calibration_reference_ms),
+    };
+    println!("Time budget scale: {:.2}", time_scale);
+
+    let settings = RunSettings {
+        release: options.release,
+        time_scale,
+        retries: options.retries,
+    };
+
+    for warning in check_config_scores(&config) {
+        eprintln!("\x1b[33mConfig warning: {}\x1b[0m", warning);
+    }
 
     let mut report = Report {
         exercises: Vec::new(),
@@ -68,24 +237,70 @@ fn main() {
             total_exercises: 0,
             total_successes: 0,
             total_failures: 0,
+            total_flaky: 0,
             total_score: 0,
+            max_total_score: 0,
             total_time: 0,
+            time_budget_scale: time_scale,
+            weighted_score: 0.0,
+            percentage: 0.0,
+            difficulties: difficulty_statistics(&config),
         },
+        flaky: Vec::new(),
+        flaky_history: flaky::load_history("report.json"),
     };
 
     
-    evaluate_exercises_from_config(mode, config, &mut report);
+    evaluate_exercises_from_config(&options.mode, &settings, config, &mut report);
 
     
     report.statistics.total_time = start_time.elapsed().as_secs();
     report.statistics.total_exercises = report.statistics.total_successes + report.statistics.total_failures;
+    finalize_statistics(&mut report.statistics);
+
+    let flaky_names = report
+        .exercises
+        .iter()
+        .filter(|exercise| exercise.status == ExerciseStatus::Flaky)
+        .map(|exercise| exercise.name.clone())
+        .collect();
+    flaky::record_run(&mut report.flaky_history, flaky_names);
+    report.flaky = flaky::summarize(&report.flaky_history);
 
     
     println!("\nSummary:");
     println!("Total exercises: {}", report.statistics.total_exercises);
     println!("Total successes: {}", report.statistics.total_successes);
     println!("Total failures: {}", report.statistics.total_failures);
-    println!("Total score: {}", report.statistics.total_score);
+    println!("Total flaky: {}", report.statistics.total_flaky);
+    println!(
+        "Total score: {}/{}",
+        report.statistics.total_score, report.statistics.max_total_score
+    );
+    println!("Percentage: {:.2}%", report.statistics.percentage);
+    for tier in &report.statistics.difficulties {
+        println!(
+            "  {:?}: {}/{} exercises, score {}/{}, weighted {:.2}/{} ({:.2}%)",
+            tier.difficulty,
+            tier.total_successes,
+            tier.total_exercises,
+            tier.score,
+            tier.max_score,
+            tier.weighted_score,
+            tier.weight,
+            tier.percentage
+        );
+    }
+
+    if !report.flaky.is_empty() {
+        println!(
+            "\nFlaky exercises (last {} runs):",
+            report.flaky_history.len()
+        );
+        for entry in &report.flaky {
+            println!("  {}: {} run(s) needed retries", entry.name, entry.flaky_runs);
+        }
+    }
 
     
     if let Err(e) = save_report_to_json("report.json", &report) {
@@ -94,26 +309,193 @@ fn main() {
 }
 
 
+// 解析命令行参数
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut iter = args.iter();
+    let mode = match iter.next() {
+        Some(mode) if mode == "watch" || mode == "all" || mode == "calibrate" => mode.clone(),
+        Some(mode) => return Err(format!("Unknown command: {}", mode)),
+        None => return Err("Please provide a command: 'watch', 'all' or 'calibrate'".to_string()),
+    };
+
+    let mut options = Options {
+        mode,
+        release: false,
+        time_scale: None,
+        retries: 0,
+    };
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--release" => options.release = true,
+            "--time-scale" => {
+                let value = iter.next().ok_or("--time-scale requires a value")?;
+                let scale: f64 = value
+                    .parse()
+                    .map_err(|_| format!("Invalid --time-scale value: {}", value))?;
+                if !scale.is_finite() || scale < 1.0 {
+                    return Err(format!("--time-scale must be at least 1.0, got {}", value));
+                }
+                options.time_scale = Some(scale);
+            }
+            "--retries" => {
+                let value = iter.next().ok_or("--retries requires a value")?;
+                options.retries = value
+                    .parse()
+                    .map_err(|_| format!("Invalid --retries value: {}", value))?;
+            }
+            _ => return Err(format!("Unknown option: {}", arg)),
+        }
+    }
+
+    Ok(options)
+}
+
+// 根据本机与参考机器运行校准任务的耗时之比，计算时间限制的放大倍数
+fn calibrate_time_scale(reference_ms: f64) -> f64 {
+    (measure_calibration_ms() / reference_ms).max(1.0)
+}
+
+// 运行校准任务，返回最快一次的耗时（毫秒）
+fn measure_calibration_ms() -> f64 {
+    (0..CALIBRATION_ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(calibration_workload(black_box(200_000)));
+            start.elapsed().as_secs_f64() * 1000.0
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+// 校准任务：试除法统计素数个数，与困难题的计算负载相近
+fn calibration_workload(limit: u64) -> u64 {
+    let mut count = 0;
+    for n in 2..limit {
+        let mut d = 2;
+        let mut prime = true;
+        while d * d <= n {
+            if n % d == 0 {
+                prime = false;
+                break;
+            }
+            d += 1;
+        }
+        if prime {
+            count += 1;
+        }
+    }
+    count
+}
+
 fn load_exercise_config(file_path: &str) -> Result<ExerciseConfig, io::Error> {
     let file = File::open(file_path)?;
     let config: ExerciseConfig = serde_json::from_reader(file)?;
     Ok(config)
 }
 
+// 按难度列出配置中的习题
+fn exercises_by_difficulty(config: &ExerciseConfig) -> [(Difficulty, &[Exercise]); 3] {
+    [
+        (Difficulty::Easy, &config.easy),
+        (Difficulty::Normal, &config.normal),
+        (Difficulty::Hard, &config.hard),
+    ]
+}
+
+// 检查每个难度的习题分值之和是否等于该难度的分值
+fn check_config_scores(config: &ExerciseConfig) -> Vec<String> {
+    exercises_by_difficulty(config)
+        .into_iter()
+        .filter_map(|(difficulty, exercises)| {
+            let configured: i32 = exercises.iter().map(|exercise| exercise.score).sum();
+            let expected = config.weights.get(difficulty);
+            (configured != expected).then(|| {
+                format!(
+                    "{:?} exercises add up to {} points, expected {}",
+                    difficulty, configured, expected
+                )
+            })
+        })
+        .collect()
+}
+
+// 初始化各难度的统计，满分取自配置
+fn difficulty_statistics(config: &ExerciseConfig) -> Vec<DifficultyStatistics> {
+    exercises_by_difficulty(config)
+        .into_iter()
+        .map(|(difficulty, exercises)| DifficultyStatistics {
+            difficulty,
+            total_exercises: 0,
+            total_successes: 0,
+            total_failures: 0,
+            score: 0,
+            max_score: exercises.iter().map(|exercise| exercise.score).sum(),
+            weight: config.weights.get(difficulty),
+            weighted_score: 0.0,
+            percentage: 0.0,
+        })
+        .collect()
+}
+
+// 汇总满分，按各难度的分值折算得分，并计算总的百分比
+fn finalize_statistics(statistics: &mut Statistics) {
+    for tier in statistics.difficulties.iter_mut() {
+        if tier.max_score > 0 {
+            let ratio = tier.score as f64 / tier.max_score as f64;
+            tier.weighted_score = ratio * tier.weight as f64;
+            tier.percentage = ratio * 100.0;
+        }
+    }
 
-fn evaluate_exercises_from_config(mode: &str, config: ExerciseConfig, report: &mut Report) {
-    let all_exercises = [config.easy, config.normal, config.hard].concat();
+    statistics.max_total_score = statistics.difficulties.iter().map(|tier| tier.max_score).sum();
+    let total_weight: i32 = statistics.difficulties.iter().map(|tier| tier.weight).sum();
+    statistics.weighted_score = statistics.difficulties.iter().map(|tier| tier.weighted_score).sum();
+    if total_weight > 0 {
+        statistics.percentage = statistics.weighted_score / total_weight as f64 * 100.0;
+    }
+}
 
-    for exercise in all_exercises {
+
+fn evaluate_exercises_from_config(
+    mode: &str,
+    settings: &RunSettings,
+    config: ExerciseConfig,
+    report: &mut Report,
+) {
+    let all_exercises = [
+        (Difficulty::Easy, config.easy),
+        (Difficulty::Normal, config.normal),
+        (Difficulty::Hard, config.hard),
+    ]
+    .into_iter()
+    .flat_map(|(difficulty, exercises)| exercises.into_iter().map(move |exercise| (difficulty, exercise)));
+
+    for (difficulty, exercise) in all_exercises {
         println!("\nEvaluating {}: {}", exercise.exercise_type, exercise.name);
-        let result = evaluate_exercise(&exercise);
+        let outcome = evaluate_exercise(&exercise, settings);
+        print_failed_tests(&outcome.tests);
 
+        let result = outcome.passed;
         let score = if result { exercise.score } else { 0 };
+        let status = if !result {
+            ExerciseStatus::Failed
+        } else if outcome.attempts.len() > 1 {
+            ExerciseStatus::Flaky
+        } else {
+            ExerciseStatus::Passed
+        };
 
         report.exercises.push(ExerciseResult {
             name: exercise.name.clone(),
+            path: exercise.path.clone(),
+            exercise_type: exercise.exercise_type.clone(),
+            difficulty,
             result,
             score,
+            max_score: exercise.score,
+            status,
+            attempts: outcome.attempts,
+            tests: outcome.tests,
         });
 
         if result {
@@ -121,9 +503,27 @@ fn evaluate_exercises_from_config(mode: &str, config: ExerciseConfig, report: &m
         } else {
             report.statistics.total_failures += 1;
         }
+        if status == ExerciseStatus::Flaky {
+            report.statistics.total_flaky += 1;
+        }
 
         report.statistics.total_score += score;
 
+        if let Some(tier) = report
+            .statistics
+            .difficulties
+            .iter_mut()
+            .find(|tier| tier.difficulty == difficulty)
+        {
+            tier.total_exercises += 1;
+            if result {
+                tier.total_successes += 1;
+            } else {
+                tier.total_failures += 1;
+            }
+            tier.score += score;
+        }
+
         if mode == "watch" && !ask_to_continue() {
             break;
         }
@@ -131,52 +531,67 @@ fn evaluate_exercises_from_config(mode: &str, config: ExerciseConfig, report: &m
 }
 
 
-fn evaluate_exercise(exercise: &Exercise) -> bool {
-    let exercise_path = PathBuf::from(&format!("./exercises/{}", exercise.path));
+fn evaluate_exercise(exercise: &Exercise, settings: &RunSettings) -> ExerciseOutcome {
+    let exercise_path = format!("./exercises/{}", exercise.path);
+    let exercise_path = Path::new(&exercise_path);
+    let release = settings.release && exercise.perf_gated;
     match exercise.exercise_type.as_str() {
-        "single_file" => evaluate_single_file(&exercise_path),
-        "cargo_project" => evaluate_cargo_project(&exercise_path),
+        "single_file" => evaluate_single_file(exercise_path, release, settings),
+        "cargo_project" => evaluate_cargo_project(exercise_path, release, settings),
         _ => {
             eprintln!("Unknown exercise type: {}", exercise.exercise_type);
-            false
+            ExerciseOutcome::default()
         }
     }
 }
 
 // 评测单文件 Rust 习题（使用 rustc --test 并执行测试）
-fn evaluate_single_file(file_path: &PathBuf) -> bool {
+fn evaluate_single_file(file_path: &Path, release: bool, settings: &RunSettings) -> ExerciseOutcome {
     // 获取文件名（不带扩展名）
     let test_binary = file_path.with_extension(""); 
 
     // 编译测试文件
-    let compile_output = Command::new("rustc")
+    let mut compile_command = Command::new("rustc");
+    compile_command
         .arg("--test")  // 使用 rustc --test 进行编译
         .arg(file_path)
         .arg("-o")
-        .arg(&test_binary)  // 指定输出文件
-        .output();
+        .arg(&test_binary);  // 指定输出文件
+    if release {
+        compile_command.arg("-O");
+    }
+    let compile_output = compile_command.output();
 
     if let Ok(output) = compile_output {
         if output.status.success() {
             // 编译成功，运行测试二进制文件
-            let test_output = Command::new(&test_binary)
-                .output();
-
-            let test_passed = match test_output {
-                Ok(test_run) => {
-                    if test_run.status.success() {
-                        println!("\x1b[32m{}: TEST PASSED\x1b[0m", file_path.display());
-                        true
-                    } else {
-                        println!("\x1b[31m{}: TEST FAILED\x1b[0m", file_path.display());
-                        false
-                    }
+            let binaries = [test_binary.clone()];
+            let test_run = run_tests_with_retries(settings.retries, || {
+                run_tests(&binaries, &|binary| {
+                    let mut command = Command::new(binary);
+                    command.env(TIME_BUDGET_ENV, settings.time_scale.to_string());
+                    command
+                })
+            });
+
+            match &test_run {
+                Some(outcome) if outcome.passed && outcome.attempts.len() > 1 => {
+                    println!(
+                        "\x1b[33m{}: TEST FLAKY (passed on attempt {})\x1b[0m",
+                        file_path.display(),
+                        outcome.attempts.len()
+                    );
+                }
+                Some(outcome) if outcome.passed => {
+                    println!("\x1b[32m{}: TEST PASSED\x1b[0m", file_path.display());
+                }
+                Some(_) => {
+                    println!("\x1b[31m{}: TEST FAILED\x1b[0m", file_path.display());
                 }
-                Err(_) => {
+                None => {
                     eprintln!("Error running test executable for {}", file_path.display());
-                    false
                 }
-            };
+            }
 
             // 删除测试二进制文件
             if let Err(e) = fs::remove_file(&test_binary) {
@@ -185,27 +600,59 @@ fn evaluate_single_file(file_path: &PathBuf) -> bool {
                 println!("Successfully removed test binary: {}", test_binary.display());
             }
 
-            return test_passed;
+            test_run.unwrap_or_default()
         } else {
             // 编译失败
             eprintln!("\x1b[31m{}: COMPILATION FAILED\x1b[0m", file_path.display());
-            return false;
+            ExerciseOutcome::default()
         }
     } else {
         eprintln!("Error executing rustc --test for {}", file_path.display());
-        return false;
+        ExerciseOutcome::default()
     }
 }
 
 // 评测 Cargo 项目
-fn evaluate_cargo_project(proj_path: &PathBuf) -> bool {
-    let build_success = run_cargo_command(proj_path, "build");
-    let test_success = run_cargo_command(proj_path, "test");
-    let clippy_success = run_cargo_command(proj_path, "clippy");
-
-    let passed = build_success && test_success && clippy_success;
-
-    if passed {
+fn evaluate_cargo_project(proj_path: &Path, release: bool, settings: &RunSettings) -> ExerciseOutcome {
+    let time_scale = settings.time_scale;
+    let build_success = run_cargo_command(proj_path, "build", release, time_scale);
+    // 构建失败时测试必然失败，无需运行；重试时只重新运行测试二进制，不再编译
+    let test_build = if build_success {
+        build_test_binaries(proj_path, release, time_scale)
+    } else {
+        None
+    };
+    let test_run = test_build
+        .and_then(|test_build| {
+            run_tests_with_retries(settings.retries, || {
+                let mut outcome = run_tests(&test_build.binaries, &|binary| {
+                    let mut command = Command::new(binary);
+                    command
+                        .current_dir(proj_path)
+                        .env(TIME_BUDGET_ENV, time_scale.to_string());
+                    command
+                })?;
+                // 文档测试不会生成测试二进制，与 cargo test 一样另外运行
+                if test_build.has_lib {
+                    let doc_tests = run_doc_tests(proj_path, release, time_scale)?;
+                    outcome.passed &= doc_tests.passed;
+                    outcome.tests.extend(doc_tests.tests);
+                }
+                Some(outcome)
+            })
+        })
+        .unwrap_or_default();
+    let clippy_success = run_cargo_command(proj_path, "clippy", false, time_scale);
+
+    let passed = build_success && test_run.passed && clippy_success;
+
+    if passed && test_run.attempts.len() > 1 {
+        println!(
+            "\x1b[33m{}: FLAKY (tests passed on attempt {})\x1b[0m",
+            proj_path.display(),
+            test_run.attempts.len()
+        );
+    } else if passed {
         println!("\x1b[32m{}: PASSED\x1b[0m", proj_path.display());
     } else {
         println!("\x1b[31m{}: FAILED\x1b[0m", proj_path.display());
@@ -213,15 +660,29 @@ fn evaluate_cargo_project(proj_path: &PathBuf) -> bool {
 
     clean_target_directory(proj_path);
 
-    passed
+    ExerciseOutcome {
+        passed,
+        attempts: test_run.attempts,
+        tests: test_run.tests,
+    }
 }
 
-// 运行 Cargo 命令
-fn run_cargo_command(proj_path: &PathBuf, command: &str) -> bool {
-    let output = Command::new("cargo")
-        .arg(command)
+// 构造 Cargo 命令
+fn cargo_command(proj_path: &Path, command: &str, release: bool, time_scale: f64) -> Command {
+    let mut cargo = Command::new("cargo");
+    cargo.arg(command);
+    if release {
+        cargo.arg("--release");
+    }
+    cargo
         .current_dir(proj_path)
-        .output();
+        .env(TIME_BUDGET_ENV, time_scale.to_string());
+    cargo
+}
+
+// 运行 Cargo 命令
+fn run_cargo_command(proj_path: &Path, command: &str, release: bool, time_scale: f64) -> bool {
+    let output = cargo_command(proj_path, command, release, time_scale).output();
 
     match output {
         Ok(out) => out.status.success(),
@@ -229,8 +690,120 @@ fn run_cargo_command(proj_path: &PathBuf, command: &str) -> bool {
     }
 }
 
+// cargo test --no-run 的结果：测试二进制，以及是否有需要运行文档测试的 lib 目标
+#[derive(Debug, Default, PartialEq)]
+struct TestBuild {
+    binaries: Vec<PathBuf>,
+    has_lib: bool,
+}
+
+// 编译测试但不运行（cargo test --no-run），返回生成的测试二进制；编译失败时返回 None
+fn build_test_binaries(proj_path: &Path, release: bool, time_scale: f64) -> Option<TestBuild> {
+    let mut command = cargo_command(proj_path, "test", release, time_scale);
+    command.args(["--no-run", "--message-format", "json"]);
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_test_build(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// 从 cargo 的 JSON 消息中找出测试目标的可执行文件，以及是否编译了 lib 目标
+fn parse_test_build(stdout: &str) -> TestBuild {
+    let mut test_build = TestBuild::default();
+    let artifacts = stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|message| message["reason"] == "compiler-artifact");
+    for artifact in artifacts {
+        if artifact["profile"]["test"] == true {
+            if let Some(executable) = artifact["executable"].as_str() {
+                test_build.binaries.push(PathBuf::from(executable));
+            }
+        } else if artifact["target"]["kind"]
+            .as_array()
+            .is_some_and(|kinds| kinds.iter().any(|kind| kind == "lib"))
+        {
+            test_build.has_lib = true;
+        }
+    }
+    test_build
+}
+
+// 运行 lib 目标的文档测试（cargo test --doc），解析普通输出
+fn run_doc_tests(proj_path: &Path, release: bool, time_scale: f64) -> Option<ExerciseOutcome> {
+    let mut command = cargo_command(proj_path, "test", release, time_scale);
+    command.arg("--doc");
+    let output = command.output().ok()?;
+    Some(ExerciseOutcome {
+        passed: output.status.success(),
+        tests: libtest::parse_plain_output(&String::from_utf8_lossy(&output.stdout)),
+        ..ExerciseOutcome::default()
+    })
+}
+
+// 运行测试阶段，失败时最多重试 retries 次，并记录每次运行的耗时
+fn run_tests_with_retries(
+    retries: u32,
+    run_attempt: impl Fn() -> Option<ExerciseOutcome>,
+) -> Option<ExerciseOutcome> {
+    let mut attempts = Vec::new();
+    loop {
+        let start = Instant::now();
+        let mut outcome = run_attempt()?;
+        attempts.push(Attempt {
+            result: outcome.passed,
+            duration_ms: start.elapsed().as_secs_f64() * 1000.0,
+        });
+
+        if outcome.passed || attempts.len() > retries as usize {
+            outcome.attempts = attempts;
+            return Some(outcome);
+        }
+        println!("Retrying tests ({}/{})...", attempts.len(), retries);
+    }
+}
+
+// 依次运行测试二进制并收集每个测试用例的结果；工具链不支持 JSON 输出时解析普通输出
+fn run_tests(binaries: &[PathBuf], make_command: &impl Fn(&Path) -> Command) -> Option<ExerciseOutcome> {
+    let mut outcome = ExerciseOutcome {
+        passed: true,
+        ..ExerciseOutcome::default()
+    };
+    for binary in binaries {
+        let json = *JSON_OUTPUT.get_or_init(|| libtest::supports_json_output(make_command(binary)));
+        let mut command = make_command(binary);
+        if json {
+            libtest::enable_json_output(&mut command);
+        }
+        let output = command.output().ok()?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let tests = if json {
+            libtest::parse_json_output(&stdout).unwrap_or_default()
+        } else {
+            libtest::parse_plain_output(&stdout)
+        };
+        outcome.passed &= output.status.success();
+        outcome.tests.extend(tests);
+    }
+    Some(outcome)
+}
+
+// 输出失败的测试用例及其 panic 信息
+fn print_failed_tests(tests: &[TestCaseResult]) {
+    for test in tests.iter().filter(|test| !test.result) {
+        println!("\x1b[31m  FAILED {} ({:.1} ms)\x1b[0m", test.name, test.duration_ms);
+        if let Some(message) = &test.panic_message {
+            for line in message.lines() {
+                println!("    {}", line);
+            }
+        }
+    }
+}
+
 // 清理 target 目录
-fn clean_target_directory(proj_path: &PathBuf) {
+fn clean_target_directory(proj_path: &Path) {
     let target_dir = proj_path.join("target");
 
     if target_dir.exists() {
@@ -256,3 +829,124 @@ fn save_report_to_json(file_name: &str, report: &Report) -> io::Result<()> {
     serde_json::to_writer_pretty(file, report)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_test_build() {
+        let stdout = [
+            r#"{"reason":"compiler-artifact","target":{"kind":["lib"]},"profile":{"test":false},"executable":null}"#,
+            r#"{"reason":"compiler-artifact","target":{"kind":["bin"]},"profile":{"test":true},"executable":"/t/deps/app-1"}"#,
+            r#"{"reason":"compiler-artifact","target":{"kind":["test"]},"profile":{"test":true},"executable":"/t/deps/tests-2"}"#,
+            r#"{"reason":"compiler-artifact","target":{"kind":["bin"]},"profile":{"test":false},"executable":"/t/app"}"#,
+            r#"{"reason":"build-finished","success":true}"#,
+            "garbage",
+            r#"{"reason":"compiler-artifact","target":{"kind":["te"#,
+        ]
+        .join("\n");
+        assert_eq!(
+            parse_test_build(&stdout),
+            TestBuild {
+                binaries: vec![PathBuf::from("/t/deps/app-1"), PathBuf::from("/t/deps/tests-2")],
+                has_lib: true,
+            }
+        );
+        assert_eq!(parse_test_build(""), TestBuild::default());
+    }
+
+    // 各难度分值不是默认的 20/30/50，困难题的分值之和与其不符，普通题为空
+    fn weighted_config() -> ExerciseConfig {
+        serde_json::from_str(
+            r#"{
+                "easy": [
+                    {"name": "a", "path": "easy/a.rs", "type": "single_file", "score": 5},
+                    {"name": "b", "path": "easy/b.rs", "type": "single_file", "score": 5}
+                ],
+                "normal": [],
+                "hard": [
+                    {"name": "c", "path": "hard/c", "type": "cargo_project", "score": 20},
+                    {"name": "d", "path": "hard/d", "type": "cargo_project", "score": 30}
+                ],
+                "weights": {"easy": 10, "normal": 30, "hard": 60}
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_check_config_scores() {
+        let mut config = weighted_config();
+        assert_eq!(
+            check_config_scores(&config),
+            [
+                "Normal exercises add up to 0 points, expected 30",
+                "Hard exercises add up to 50 points, expected 60",
+            ]
+        );
+
+        config.weights = TierWeights {
+            easy: 10,
+            normal: 0,
+            hard: 50,
+        };
+        assert!(check_config_scores(&config).is_empty());
+    }
+
+    #[test]
+    fn test_finalize_statistics() {
+        let config = weighted_config();
+        let mut statistics = Statistics {
+            total_exercises: 4,
+            total_successes: 2,
+            total_failures: 2,
+            total_flaky: 0,
+            total_score: 35,
+            max_total_score: 0,
+            total_time: 0,
+            time_budget_scale: 1.0,
+            weighted_score: 0.0,
+            percentage: 0.0,
+            difficulties: difficulty_statistics(&config),
+        };
+        statistics.difficulties[0].score = 5;
+        statistics.difficulties[2].score = 30;
+        finalize_statistics(&mut statistics);
+
+        let tiers: Vec<(i32, i32, f64, f64)> = statistics
+            .difficulties
+            .iter()
+            .map(|tier| (tier.max_score, tier.weight, tier.weighted_score, tier.percentage))
+            .collect();
+        // 普通题没有习题，满分为 0，不计得分但仍占 30 分
+        assert_eq!(
+            tiers,
+            [(10, 10, 5.0, 50.0), (0, 30, 0.0, 0.0), (50, 60, 36.0, 60.0)]
+        );
+        assert_eq!(statistics.max_total_score, 60);
+        assert!((statistics.weighted_score - 41.0).abs() < 1e-9);
+        assert!((statistics.percentage - 41.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_calibration() {
+        assert_eq!(calibration_workload(100), 25);
+        assert_eq!(calibration_workload(200_000), 17_984);
+
+        let measured = measure_calibration_ms();
+        assert!(measured.is_finite() && measured > 0.0, "{measured}");
+        // 本机比参考机器快时不缩短时间限制，慢时按比例放宽
+        assert_eq!(calibrate_time_scale(f64::MAX), 1.0);
+        assert!(calibrate_time_scale(measured / 1000.0) > 1.0);
+
+        let config: ExerciseConfig =
+            serde_json::from_str(r#"{"easy": [], "normal": [], "hard": []}"#).unwrap();
+        assert_eq!(config.calibration_reference_ms, DEFAULT_CALIBRATION_REFERENCE_MS);
+        let config: ExerciseConfig = serde_json::from_str(
+            r#"{"easy": [], "normal": [], "hard": [], "calibration_reference_ms": 12.5}"#,
+        )
+        .unwrap();
+        assert_eq!(config.calibration_reference_ms, 12.5);
+    }
+}